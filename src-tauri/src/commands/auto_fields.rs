@@ -0,0 +1,215 @@
+use super::frontmatter_dates::normalize_date;
+use super::save_frontmatter::{AutoValue, FrontmatterField};
+use super::search::frontmatter_line_count;
+use std::collections::HashMap;
+
+/// 生成 slug 时使用的标题字段
+const TITLE_FIELD: &str = "title";
+/// 每分钟阅读的中日韩字符数
+const CJK_PER_MINUTE: f64 = 300.0;
+/// 每分钟阅读的其他语言单词数
+const WORDS_PER_MINUTE: f64 = 200.0;
+
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'   // 平假名、片假名
+        | '\u{3400}'..='\u{4DBF}' // CJK 扩展 A
+        | '\u{4E00}'..='\u{9FFF}' // CJK 统一表意文字
+        | '\u{AC00}'..='\u{D7AF}' // 谚文音节
+        | '\u{F900}'..='\u{FAFF}' // CJK 兼容表意文字
+        | '\u{20000}'..='\u{2FA1F}')
+}
+
+/// 正文字数：中日韩字符逐字计数，其他语言按单词计数，代码块不计
+fn count_words(body: &str) -> (usize, usize) {
+    let mut cjk = 0;
+    let mut words = 0;
+    let mut in_code = false;
+    for line in body.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        let mut in_word = false;
+        for c in line.chars() {
+            if is_cjk(c) {
+                cjk += 1;
+                in_word = false;
+            } else if c.is_alphanumeric() {
+                if !in_word {
+                    words += 1;
+                }
+                in_word = true;
+            } else if c != '\'' && c != '’' {
+                in_word = false;
+            }
+        }
+    }
+    (cjk, words)
+}
+
+/// 由标题生成 slug：ASCII 转为小写，中日韩等字符保留，其余字符替换为连字符
+pub(crate) fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.trim().chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+fn is_blank(value: Option<&serde_json::Value>) -> bool {
+    match value {
+        None | Some(serde_json::Value::Null) => true,
+        Some(serde_json::Value::String(s)) => s.trim().is_empty(),
+        _ => false,
+    }
+}
+
+/// 去掉 frontmatter 后的正文，用于判断正文是否有变化
+fn body_of(content: &str) -> &str {
+    let skip = frontmatter_line_count(content);
+    let offset: usize = content
+        .split_inclusive('\n')
+        .take(skip)
+        .map(|line| line.len())
+        .sum();
+    content[offset..].trim()
+}
+
+/// 保存前根据字段配置填写自动维护的字段。
+/// `previous` 为文件保存前的内容，新文件为 None。
+pub(crate) fn apply_auto_fields(
+    fields: &[FrontmatterField],
+    frontmatter: &mut HashMap<String, serde_json::Value>,
+    body: &str,
+    previous: Option<&str>,
+) -> Result<(), String> {
+    let body_changed = previous.is_none_or(|previous| body_of(previous) != body.trim());
+    let (cjk, words) = count_words(body);
+
+    for field in fields {
+        let Some(auto) = field.auto else {
+            continue;
+        };
+        let current = frontmatter.get(&field.title);
+        let value = match auto {
+            AutoValue::LastModified => {
+                if !body_changed && !is_blank(current) {
+                    continue;
+                }
+                let now = chrono::Utc::now().to_rfc3339();
+                serde_json::Value::String(normalize_date(field, &serde_json::Value::String(now))?)
+            }
+            AutoValue::Slug => {
+                if !is_blank(current) {
+                    continue;
+                }
+                let Some(title) = frontmatter.get(TITLE_FIELD).and_then(|t| t.as_str()) else {
+                    continue;
+                };
+                let slug = slugify(title);
+                if slug.is_empty() {
+                    continue;
+                }
+                serde_json::Value::String(slug)
+            }
+            AutoValue::WordCount => (cjk + words).into(),
+            AutoValue::ReadingTime => {
+                let minutes = cjk as f64 / CJK_PER_MINUTE + words as f64 / WORDS_PER_MINUTE;
+                // 有内容时至少 1 分钟
+                let minutes = if cjk + words == 0 {
+                    0
+                } else {
+                    (minutes.ceil() as u64).max(1)
+                };
+                minutes.into()
+            }
+            AutoValue::Uuid => {
+                if !is_blank(current) {
+                    continue;
+                }
+                serde_json::Value::String(uuid::Uuid::new_v4().to_string())
+            }
+        };
+        frontmatter.insert(field.title.clone(), value);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auto_field(title: &str, auto: AutoValue) -> FrontmatterField {
+        FrontmatterField {
+            title: title.to_string(),
+            field_type: "string".to_string(),
+            auto: Some(auto),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn slugify_lowercases_and_joins_words() {
+        assert_eq!(slugify("  Hello, World!  "), "hello-world");
+        assert_eq!(slugify("Rust & Tauri 2.0"), "rust-tauri-2-0");
+        assert_eq!(slugify("你好 世界"), "你好-世界");
+        assert_eq!(slugify("--Already--slugged--"), "already-slugged");
+        assert_eq!(slugify("!!!"), "");
+    }
+
+    #[test]
+    fn count_words_splits_cjk_and_skips_code() {
+        assert_eq!(
+            count_words("Don't panic, 你好\n```\nlet x = 1;\n```\nbye"),
+            (2, 3)
+        );
+    }
+
+    #[test]
+    fn auto_fields_fill_only_blank_values() {
+        let fields = [
+            auto_field("slug", AutoValue::Slug),
+            auto_field("id", AutoValue::Uuid),
+            auto_field("words", AutoValue::WordCount),
+            auto_field("reading", AutoValue::ReadingTime),
+        ];
+        let mut frontmatter = HashMap::from([
+            ("title".to_string(), "Hello World".into()),
+            ("id".to_string(), "keep-me".into()),
+        ]);
+        apply_auto_fields(&fields, &mut frontmatter, "one two three", None).unwrap();
+        assert_eq!(frontmatter["slug"], "hello-world");
+        assert_eq!(frontmatter["id"], "keep-me");
+        assert_eq!(frontmatter["words"], 3);
+        assert_eq!(frontmatter["reading"], 1);
+
+        frontmatter.insert("title".to_string(), "Renamed".into());
+        apply_auto_fields(&fields, &mut frontmatter, "", None).unwrap();
+        assert_eq!(frontmatter["slug"], "hello-world");
+        assert_eq!(frontmatter["reading"], 0);
+    }
+
+    #[test]
+    fn last_modified_waits_for_a_body_change() {
+        let fields = [FrontmatterField {
+            field_type: "dateandtime".to_string(),
+            ..auto_field("updated", AutoValue::LastModified)
+        }];
+        let previous = "---\nupdated: 2020-01-01T00:00:00+00:00\n---\nbody\n";
+        let mut frontmatter =
+            HashMap::from([("updated".to_string(), "2020-01-01T00:00:00+00:00".into())]);
+        apply_auto_fields(&fields, &mut frontmatter, "body\n", Some(previous)).unwrap();
+        assert_eq!(frontmatter["updated"], "2020-01-01T00:00:00+00:00");
+
+        apply_auto_fields(&fields, &mut frontmatter, "new body\n", Some(previous)).unwrap();
+        assert_ne!(frontmatter["updated"], "2020-01-01T00:00:00+00:00");
+    }
+}
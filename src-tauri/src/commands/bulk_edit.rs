@@ -0,0 +1,443 @@
+use super::frontmatter_edit::FrontmatterLines;
+use super::frontmatter_query::parse_query;
+use super::search::{passes_filter, resolve_folder};
+use super::undo::{write_changes, ChangeSummary, FileChange, SkippedFile};
+use super::workspace_config::require_workspace;
+use super::workspace_index::markdown_files;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, PathBuf};
+use tauri::{AppHandle, Window};
+
+/// 对单个 frontmatter 字段进行的修改
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum FrontmatterPatch {
+    /// 将字段设为 `value`，字段不存在时添加
+    Set {
+        field: String,
+        value: serde_json::Value,
+    },
+    /// 删除字段
+    Unset { field: String },
+    /// 向数组字段追加尚不存在的值
+    Append { field: String, values: Vec<String> },
+    /// 从数组字段中删除值
+    Remove { field: String, values: Vec<String> },
+}
+
+impl FrontmatterPatch {
+    fn field(&self) -> &str {
+        match self {
+            FrontmatterPatch::Set { field, .. }
+            | FrontmatterPatch::Unset { field }
+            | FrontmatterPatch::Append { field, .. }
+            | FrontmatterPatch::Remove { field, .. } => field,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let field = self.field();
+        if field.trim().is_empty() || field.contains('.') {
+            return Err("Only top-level fields can be changed".to_string());
+        }
+        match self {
+            FrontmatterPatch::Append { values, .. } | FrontmatterPatch::Remove { values, .. }
+                if values.iter().all(|v| v.trim().is_empty()) =>
+            {
+                Err(format!("No values given for \"{}\"", field))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// 在内存中应用修改，返回 frontmatter 是否变化
+    fn apply(&self, lines: &mut FrontmatterLines) -> Result<bool, String> {
+        match self {
+            FrontmatterPatch::Set { field, value } => {
+                let value = serde_yaml::to_value(value)
+                    .map_err(|e| format!("Invalid value for \"{}\": {}", field, e))?;
+                lines.set_field(field, &value)
+            }
+            FrontmatterPatch::Unset { field } => Ok(lines.remove_field(field)),
+            FrontmatterPatch::Append { field, values } => {
+                let values: Vec<String> = values
+                    .iter()
+                    .map(|v| v.trim().to_string())
+                    .filter(|v| !v.is_empty())
+                    .collect();
+                lines.append_values(field, &values)
+            }
+            FrontmatterPatch::Remove { field, values } => lines.map_values(field, |value| {
+                (!values.iter().any(|v| v.trim() == value)).then(|| value.to_string())
+            }),
+        }
+    }
+
+    fn description(&self) -> String {
+        match self {
+            FrontmatterPatch::Set { field, value } => format!("Set {} to {}", field, value),
+            FrontmatterPatch::Unset { field } => format!("Unset {}", field),
+            FrontmatterPatch::Append { field, values } => {
+                format!("Append {:?} to {}", values, field)
+            }
+            FrontmatterPatch::Remove { field, values } => {
+                format!("Remove {:?} from {}", values, field)
+            }
+        }
+    }
+}
+
+/// 批量修改的文件范围。`paths` 为空时从工作区中的所有 Markdown 文件中选择；
+/// `folder` 与 `query` 进一步筛选，三者至少指定一个。
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BulkEditTarget {
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// 相对工作区根目录的目录
+    pub folder: Option<String>,
+    /// frontmatter 查询，语法与搜索中的查询相同
+    pub query: Option<String>,
+}
+
+/// 预览中受影响的单个文件
+#[derive(Serialize)]
+pub struct BulkEditPreview {
+    pub path: String,
+    /// frontmatter 的逐行对比，每行以 `+`（新增）、`-`（删除）或空格（未变）开头
+    pub diff: Vec<String>,
+    /// 无法安全改写时的原因，此文件不会被修改
+    pub error: Option<String>,
+}
+
+fn validate(patches: &[FrontmatterPatch], target: &BulkEditTarget) -> Result<(), String> {
+    if patches.is_empty() {
+        return Err("No changes to apply".to_string());
+    }
+    for patch in patches {
+        patch.validate()?;
+    }
+    let blank = |s: &Option<String>| s.as_deref().is_none_or(|s| s.trim().is_empty());
+    if target.paths.is_empty() && blank(&target.folder) && blank(&target.query) {
+        return Err("No files selected".to_string());
+    }
+    Ok(())
+}
+
+/// 按范围选出的文件，以及已读出的内容；读取失败或不在工作区内的文件记录原因
+struct Selection {
+    files: Vec<(PathBuf, String)>,
+    skipped: Vec<SkippedFile>,
+}
+
+fn select_files(
+    app: &AppHandle,
+    workspace: &str,
+    target: &BulkEditTarget,
+) -> Result<Selection, String> {
+    let query = match target.query.as_deref().map(str::trim) {
+        Some(query) if !query.is_empty() => Some(parse_query(query)?),
+        _ => None,
+    };
+    let folder = target
+        .folder
+        .as_deref()
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+        .map(|f| resolve_folder(workspace, f));
+    let candidates = if target.paths.is_empty() {
+        markdown_files(app, workspace)?
+    } else {
+        let mut paths: Vec<PathBuf> = target.paths.iter().map(PathBuf::from).collect();
+        paths.sort();
+        paths.dedup();
+        paths
+    };
+
+    let mut selection = Selection {
+        files: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for path in candidates {
+        if folder
+            .as_ref()
+            .is_some_and(|folder| !path.starts_with(folder))
+        {
+            continue;
+        }
+        let path_str = path.to_string_lossy().to_string();
+        let outside =
+            !path.starts_with(workspace) || path.components().any(|c| c == Component::ParentDir);
+        if outside {
+            selection.skipped.push(SkippedFile {
+                path: path_str,
+                reason: "File is outside the workspace".to_string(),
+            });
+            continue;
+        }
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                selection.skipped.push(SkippedFile {
+                    path: path_str,
+                    reason: e.to_string(),
+                });
+                continue;
+            }
+        };
+        if passes_filter(&content, &HashMap::new(), query.as_ref()) {
+            selection.files.push((path, content));
+        }
+    }
+    Ok(selection)
+}
+
+fn is_valid_yaml(yaml: &str) -> bool {
+    yaml.trim().is_empty() || serde_yaml::from_str::<serde_yaml::Mapping>(yaml).is_ok()
+}
+
+/// 在内存中依次应用所有修改，返回修改前后的内容（未变化时为 None）。
+/// 没有 frontmatter 的文件在需要添加字段时会新建 frontmatter。
+fn rewrite(
+    content: &str,
+    patches: &[FrontmatterPatch],
+) -> Result<Option<(FrontmatterLines, FrontmatterLines)>, String> {
+    let before = FrontmatterLines::parse_or_new(content);
+    let mut after = before.clone();
+    for patch in patches {
+        patch.apply(&mut after)?;
+    }
+    // 前后修改相互抵消时同样视为未变化
+    if after.yaml() == before.yaml() {
+        return Ok(None);
+    }
+    if is_valid_yaml(&before.yaml()) && !is_valid_yaml(&after.yaml()) {
+        return Err("The change would make the frontmatter invalid".to_string());
+    }
+    Ok(Some((before, after)))
+}
+
+/// 逐行比较修改前后的文本，返回带 `+`、`-` 或空格前缀的行
+fn line_diff(before: &str, after: &str) -> Vec<String> {
+    let a: Vec<&str> = before.lines().collect();
+    let b: Vec<&str> = after.lines().collect();
+    // lcs[i][j]：a[i..] 与 b[j..] 的最长公共子序列长度
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut diff = Vec::new();
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            diff.push(format!(" {}", a[i]));
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push(format!("-{}", a[i]));
+            i += 1;
+        } else {
+            diff.push(format!("+{}", b[j]));
+            j += 1;
+        }
+    }
+    diff
+}
+
+/// Tauri 命令：预览对所选文件 frontmatter 的批量修改（dry run），不修改任何文件。
+/// 只返回会发生变化或无法修改的文件。
+#[tauri::command]
+pub async fn preview_bulk_edit(
+    app: AppHandle,
+    window: Window,
+    patches: Vec<FrontmatterPatch>,
+    target: BulkEditTarget,
+) -> Result<Vec<BulkEditPreview>, String> {
+    validate(&patches, &target)?;
+    let workspace = require_workspace(&window)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let selection = select_files(&app, &workspace, &target)?;
+        let mut previews: Vec<BulkEditPreview> = selection
+            .skipped
+            .into_iter()
+            .map(|skipped| BulkEditPreview {
+                path: skipped.path,
+                diff: Vec::new(),
+                error: Some(skipped.reason),
+            })
+            .collect();
+        for (path, content) in selection.files {
+            let path = path.to_string_lossy().to_string();
+            match rewrite(&content, &patches) {
+                Ok(Some((before, after))) => previews.push(BulkEditPreview {
+                    path,
+                    diff: line_diff(&before.yaml(), &after.yaml()),
+                    error: None,
+                }),
+                Ok(None) => {}
+                Err(e) => previews.push(BulkEditPreview {
+                    path,
+                    diff: Vec::new(),
+                    error: Some(e),
+                }),
+            }
+        }
+        Ok(previews)
+    })
+    .await
+    .map_err(|e| format!("Background thread failed: {}", e))?
+}
+
+/// Tauri 命令：对所选文件的 frontmatter 依次应用 `patches`。
+/// 只改写涉及的行，所有被修改的文件记录为一条撤销记录，完成后刷新索引与建议。
+#[tauri::command]
+pub async fn apply_bulk_edit(
+    app: AppHandle,
+    window: Window,
+    patches: Vec<FrontmatterPatch>,
+    target: BulkEditTarget,
+) -> Result<ChangeSummary, String> {
+    validate(&patches, &target)?;
+    let workspace = require_workspace(&window)?;
+    let description = patches
+        .iter()
+        .map(|patch| patch.description())
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let selection = select_files(&app, &workspace, &target)?;
+        let mut skipped = selection.skipped;
+        let mut changes = Vec::new();
+
+        for (path, before) in selection.files {
+            let after = match rewrite(&before, &patches) {
+                Ok(Some((_, after))) => after.into_content(),
+                Ok(None) => continue,
+                Err(e) => {
+                    skipped.push(SkippedFile {
+                        path: path.to_string_lossy().to_string(),
+                        reason: e,
+                    });
+                    continue;
+                }
+            };
+            changes.push(FileChange {
+                path,
+                before,
+                after,
+            });
+        }
+
+        Ok(write_changes(
+            &app,
+            &workspace,
+            description,
+            changes,
+            skipped,
+        ))
+    })
+    .await
+    .map_err(|e| format!("Background thread failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn after(content: &str, patches: &[FrontmatterPatch]) -> Option<String> {
+        rewrite(content, patches)
+            .unwrap()
+            .map(|(_, after)| after.yaml())
+    }
+
+    #[test]
+    fn line_diff_marks_added_and_removed_lines() {
+        assert_eq!(
+            line_diff(
+                "title: A\ntags: [a]\ndraft: true",
+                "title: A\ntags: [a, b]\ndraft: true"
+            ),
+            vec![" title: A", "-tags: [a]", "+tags: [a, b]", " draft: true"]
+        );
+        assert_eq!(line_diff("", "title: A"), vec!["+title: A"]);
+        assert_eq!(line_diff("title: A", ""), vec!["-title: A"]);
+        assert_eq!(line_diff("a\nb", "a\nb"), vec![" a", " b"]);
+    }
+
+    #[test]
+    fn rewrite_applies_patches_in_order() {
+        let content = "---\ntitle: Post\ntags:\n  - rust\n---\nBody\n";
+        let set = FrontmatterPatch::Set {
+            field: "draft".to_string(),
+            value: json!(false),
+        };
+        let append = FrontmatterPatch::Append {
+            field: "tags".to_string(),
+            values: vec![" tauri ".to_string(), "rust".to_string()],
+        };
+        let yaml = after(content, &[set, append]).unwrap();
+        assert!(yaml.contains("draft: false"), "{}", yaml);
+        assert!(yaml.contains("- tauri"), "{}", yaml);
+        assert_eq!(yaml.matches("- rust").count(), 1, "{}", yaml);
+
+        let unset = FrontmatterPatch::Unset {
+            field: "title".to_string(),
+        };
+        assert!(!after(content, &[unset]).unwrap().contains("title"));
+    }
+
+    #[test]
+    fn rewrite_skips_changes_that_cancel_out() {
+        let content = "---\ntags:\n  - rust\n---\n";
+        let patches = [
+            FrontmatterPatch::Append {
+                field: "tags".to_string(),
+                values: vec!["tauri".to_string()],
+            },
+            FrontmatterPatch::Remove {
+                field: "tags".to_string(),
+                values: vec!["tauri".to_string()],
+            },
+        ];
+        assert_eq!(after(content, &patches), None);
+        let unset_missing = FrontmatterPatch::Unset {
+            field: "draft".to_string(),
+        };
+        assert_eq!(after(content, &[unset_missing]), None);
+    }
+
+    #[test]
+    fn validate_rejects_nested_fields_and_empty_targets() {
+        let target = BulkEditTarget {
+            folder: Some("posts".to_string()),
+            ..Default::default()
+        };
+        let nested = FrontmatterPatch::Unset {
+            field: "cover.image".to_string(),
+        };
+        assert!(validate(&[nested], &target).is_err());
+        let blank = FrontmatterPatch::Append {
+            field: "tags".to_string(),
+            values: vec![" ".to_string()],
+        };
+        assert!(validate(&[blank], &target).is_err());
+        assert!(validate(&[], &target).is_err());
+
+        let unset = FrontmatterPatch::Unset {
+            field: "draft".to_string(),
+        };
+        assert!(validate(std::slice::from_ref(&unset), &target).is_ok());
+        assert!(validate(&[unset], &BulkEditTarget::default()).is_err());
+    }
+}
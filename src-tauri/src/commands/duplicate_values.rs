@@ -0,0 +1,433 @@
+use super::save_frontmatter::{FrontmatterField, FrontmatterSuggestion};
+use super::workspace_config::{load_workspace_config, require_workspace};
+use super::workspace_index::{ensure_index, WorkspaceIndex};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, Manager, Window};
+use unicode_normalization::UnicodeNormalization;
+
+/// 参与编辑距离比较的最短长度（按字符计），更短的值只按规范化结果比较
+const MIN_EDIT_LENGTH: usize = 5;
+/// 超过该长度的值（多为标题、摘要等自由文本）不参与编辑距离比较
+const MAX_EDIT_LENGTH: usize = 64;
+
+/// 疑似重复值中的一项
+#[derive(Serialize)]
+pub struct DuplicateValue {
+    pub value: String,
+    pub count: usize,
+    /// 与建议保留的值之间的差异：width、case、whitespace、separator、plural 或 edit_distance
+    pub reasons: Vec<&'static str>,
+}
+
+/// 同一字段中疑似重复的一组值
+#[derive(Serialize)]
+pub struct DuplicateCluster {
+    pub field: String,
+    /// 建议合并到的值（使用次数最多的值）
+    pub suggested: String,
+    /// 组内所有值的使用次数之和
+    pub total: usize,
+    pub values: Vec<DuplicateValue>,
+}
+
+/// 按 NFKC 统一字符宽度：全角 ASCII 与全角空格转为半角，半角片假名（含浊点）转为全角
+fn normalize_width(s: &str) -> String {
+    s.nfkc().collect()
+}
+
+fn collapse_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// 连字符、下划线与空白视为同一种分隔符
+fn unify_separators(s: &str) -> String {
+    s.split(|c: char| c == '-' || c == '_' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// 将最后一个英文单词还原为单数（只处理常见的规则变化）
+fn singularize(s: &str) -> String {
+    let split = s.rfind('-').map(|i| i + 1).unwrap_or(0);
+    let (head, word) = s.split_at(split);
+    if word.len() <= 3 || !word.chars().all(|c| c.is_ascii_alphabetic()) {
+        return s.to_string();
+    }
+    let singular = if let Some(stem) = word.strip_suffix("ies") {
+        format!("{}y", stem)
+    } else if ["ses", "xes", "zes", "ches", "shes"]
+        .iter()
+        .any(|suffix| word.ends_with(suffix))
+    {
+        word[..word.len() - 2].to_string()
+    } else if word.ends_with('s') && !["ss", "us", "is"].iter().any(|s| word.ends_with(s)) {
+        word[..word.len() - 1].to_string()
+    } else {
+        word.to_string()
+    };
+    format!("{}{}", head, singular)
+}
+
+type Stage = (&'static str, fn(&str) -> String);
+
+/// 依次应用的规范化步骤
+const STAGES: &[Stage] = &[
+    ("width", normalize_width),
+    ("case", str::to_lowercase),
+    ("whitespace", collapse_whitespace),
+    ("separator", unify_separators),
+    ("plural", singularize),
+];
+
+fn normalize(value: &str) -> String {
+    STAGES
+        .iter()
+        .fold(value.to_string(), |current, (_, stage)| stage(&current))
+}
+
+/// `a` 与 `b` 之间的差异类型：依次规范化，记录使两者趋于相同的步骤
+fn differences(a: &str, b: &str) -> Vec<&'static str> {
+    let mut reasons = Vec::new();
+    let (mut a, mut b) = (a.to_string(), b.to_string());
+    for (name, stage) in STAGES {
+        if a == b {
+            return reasons;
+        }
+        let (next_a, next_b) = (stage(&a), stage(&b));
+        if next_a != a || next_b != b {
+            reasons.push(*name);
+        }
+        (a, b) = (next_a, next_b);
+    }
+    if a != b {
+        reasons.push("edit_distance");
+    }
+    reasons
+}
+
+/// 编辑距离超过 `limit` 时提前返回 None
+fn edit_distance(a: &[char], b: &[char], limit: usize) -> Option<usize> {
+    if a.len().abs_diff(b.len()) > limit {
+        return None;
+    }
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        let mut row_min = current[0];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            current[j + 1] = (previous[j] + cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+            row_min = row_min.min(current[j + 1]);
+        }
+        if row_min > limit {
+            return None;
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    Some(previous[b.len()]).filter(|d| *d <= limit)
+}
+
+/// 默认允许的编辑距离：较短的值允许 1 处差异，较长的值允许 2 处
+fn default_distance(len: usize) -> usize {
+    if len >= 8 {
+        2
+    } else {
+        1
+    }
+}
+
+fn digits(chars: &[char]) -> String {
+    chars.iter().filter(|c| c.is_ascii_digit()).collect()
+}
+
+fn find(parent: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    let mut node = i;
+    while parent[node] != root {
+        let next = parent[node];
+        parent[node] = root;
+        node = next;
+    }
+    root
+}
+
+fn union(parent: &mut [usize], a: usize, b: usize) {
+    let (ra, rb) = (find(parent, a), find(parent, b));
+    if ra != rb {
+        parent[ra] = rb;
+    }
+}
+
+/// 将一个字段的值聚类为疑似重复的组
+fn cluster_field(
+    field: &str,
+    values: &[FrontmatterSuggestion],
+    max_distance: Option<usize>,
+) -> Vec<DuplicateCluster> {
+    let normalized: Vec<String> = values.iter().map(|v| normalize(&v.value)).collect();
+    let mut parent: Vec<usize> = (0..values.len()).collect();
+
+    let mut by_key: HashMap<&str, usize> = HashMap::new();
+    for (i, key) in normalized.iter().enumerate() {
+        match by_key.get(key.as_str()) {
+            Some(&first) => union(&mut parent, first, i),
+            None => {
+                by_key.insert(key, i);
+            }
+        }
+    }
+
+    // 编辑距离只在不同的规范化结果之间比较
+    let keys: Vec<(usize, Vec<char>)> = by_key
+        .iter()
+        .map(|(key, &i)| (i, key.chars().collect::<Vec<_>>()))
+        .filter(|(_, chars)| (MIN_EDIT_LENGTH..=MAX_EDIT_LENGTH).contains(&chars.len()))
+        .collect();
+    for (n, (i, a)) in keys.iter().enumerate() {
+        for (j, b) in &keys[n + 1..] {
+            let limit = max_distance.unwrap_or_else(|| default_distance(a.len().min(b.len())));
+            // 只有数字不同的值（如 vue2 与 vue3）通常是不同的东西
+            if limit > 0 && digits(a) == digits(b) && edit_distance(a, b, limit).is_some() {
+                union(&mut parent, *i, *j);
+            }
+        }
+    }
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..values.len() {
+        let root = find(&mut parent, i);
+        groups.entry(root).or_default().push(i);
+    }
+
+    groups
+        .into_values()
+        .filter(|members| members.len() > 1)
+        .map(|mut members| {
+            members.sort_by(|&a, &b| {
+                values[b]
+                    .count
+                    .cmp(&values[a].count)
+                    .then_with(|| values[a].value.cmp(&values[b].value))
+            });
+            let suggested = values[members[0]].value.clone();
+            DuplicateCluster {
+                field: field.to_string(),
+                total: members.iter().map(|&i| values[i].count).sum(),
+                values: members
+                    .iter()
+                    .map(|&i| DuplicateValue {
+                        value: values[i].value.clone(),
+                        count: values[i].count,
+                        reasons: differences(&values[i].value, &suggested),
+                    })
+                    .collect(),
+                suggested,
+            }
+        })
+        .collect()
+}
+
+/// 字段定义中取值可枚举的字段（`string[]`、有 `allowed_values` 或 `save_as_array`），
+/// 嵌套字段以点分路径表示
+fn enumerable_fields(fields: &[FrontmatterField], prefix: &str, out: &mut HashSet<String>) {
+    for field in fields {
+        let path = format!("{}{}", prefix, field.title);
+        if field.field_type == "string[]" || field.save_as_array || !field.allowed_values.is_empty()
+        {
+            out.insert(path.clone());
+        }
+        enumerable_fields(&field.fields, &format!("{}.", path), out);
+    }
+}
+
+/// 值在文章间重复使用的字段（平均每个值至少被两篇文章使用），
+/// 排除标题、摘要等几乎每篇都不同的自由文本
+fn has_shared_values(values: &[FrontmatterSuggestion]) -> bool {
+    let uses: usize = values.iter().map(|v| v.count).sum();
+    !values.is_empty() && uses >= values.len() * 2
+}
+
+/// Tauri 命令：找出字段中疑似重复的值（大小写、空白、单复数、连字符与下划线、
+/// 全角与半角或拼写相近），可配合 `apply_value_change` 合并。
+/// 未指定 `fields` 时只检查字段定义中可枚举的字段（标签、分类等）以及值在文章间
+/// 重复使用的字段。两两比较编辑距离的开销较大，在阻塞线程中执行。
+#[tauri::command]
+pub async fn find_duplicate_values(
+    app: AppHandle,
+    window: Window,
+    fields: Option<Vec<String>>,
+    max_distance: Option<usize>,
+) -> Result<Vec<DuplicateCluster>, String> {
+    let workspace = require_workspace(&window)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        ensure_index(&app, &workspace)?;
+        let suggestions = app
+            .state::<WorkspaceIndex>()
+            .suggestions(&workspace)?
+            .field_suggestions;
+        let fields: HashSet<String> = match fields {
+            Some(fields) => fields.into_iter().collect(),
+            None => {
+                let config = load_workspace_config(&app, &workspace)?;
+                let mut enumerable = HashSet::new();
+                enumerable_fields(&config.frontmatter_fields, "", &mut enumerable);
+                for schema in &config.folder_schemas {
+                    enumerable_fields(&schema.fields, "", &mut enumerable);
+                }
+                suggestions
+                    .iter()
+                    .filter(|(field, values)| {
+                        enumerable.contains(*field) || has_shared_values(values)
+                    })
+                    .map(|(field, _)| field.clone())
+                    .collect()
+            }
+        };
+        Ok(find_clusters(&suggestions, &fields, max_distance))
+    })
+    .await
+    .map_err(|e| format!("Background thread failed: {}", e))?
+}
+
+fn find_clusters(
+    suggestions: &HashMap<String, Vec<FrontmatterSuggestion>>,
+    fields: &HashSet<String>,
+    max_distance: Option<usize>,
+) -> Vec<DuplicateCluster> {
+    let mut clusters: Vec<DuplicateCluster> = suggestions
+        .iter()
+        .filter(|(field, _)| fields.contains(*field))
+        .flat_map(|(field, values)| {
+            // 只比较字符串值，数字、日期等不会因写法不同而重复
+            let strings: Vec<FrontmatterSuggestion> = values
+                .iter()
+                .filter(|v| v.value_type == "string")
+                .cloned()
+                .collect();
+            cluster_field(field, &strings, max_distance)
+        })
+        .collect();
+
+    clusters.sort_by(|a, b| {
+        a.field
+            .cmp(&b.field)
+            .then_with(|| b.total.cmp(&a.total))
+            .then_with(|| a.suggested.cmp(&b.suggested))
+    });
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(items: &[(&str, usize)]) -> Vec<FrontmatterSuggestion> {
+        items
+            .iter()
+            .map(|(value, count)| FrontmatterSuggestion {
+                value: value.to_string(),
+                value_type: "string".to_string(),
+                count: *count,
+            })
+            .collect()
+    }
+
+    fn clusters(items: &[(&str, usize)]) -> Vec<Vec<String>> {
+        let mut clusters: Vec<Vec<String>> = cluster_field("tags", &values(items), None)
+            .into_iter()
+            .map(|c| c.values.into_iter().map(|v| v.value).collect())
+            .collect();
+        clusters.sort();
+        clusters
+    }
+
+    #[test]
+    fn normalize_unifies_width_case_separators_and_plurals() {
+        assert_eq!(normalize("ＲＵＳＴ"), "rust");
+        assert_eq!(normalize("ｶﾞｲﾄﾞ"), "ガイド");
+        assert_eq!(normalize("Web  Dev"), "web-dev");
+        assert_eq!(normalize("web_dev"), "web-dev");
+        assert_eq!(normalize("Libraries"), "library");
+        assert_eq!(normalize("boxes"), "box");
+        assert_eq!(normalize("status"), "status");
+    }
+
+    #[test]
+    fn differences_name_each_stage() {
+        assert_eq!(differences("Ｒｕｓｔ", "rust"), ["width", "case"]);
+        assert_eq!(differences("web_dev", "web-dev"), ["separator"]);
+        assert_eq!(differences("javascrpt", "javascript"), ["edit_distance"]);
+    }
+
+    #[test]
+    fn edit_distance_stops_at_the_limit() {
+        let chars = |s: &str| s.chars().collect::<Vec<_>>();
+        assert_eq!(
+            edit_distance(&chars("kitten"), &chars("sitting"), 3),
+            Some(3)
+        );
+        assert_eq!(edit_distance(&chars("kitten"), &chars("sitting"), 2), None);
+        assert_eq!(edit_distance(&chars("a"), &chars("abcd"), 2), None);
+    }
+
+    #[test]
+    fn cluster_field_groups_near_duplicates() {
+        assert_eq!(
+            clusters(&[
+                ("JavaScript", 5),
+                ("javascript", 2),
+                ("javascrpt", 1),
+                ("vue2", 3),
+                ("vue3", 3),
+                ("rust", 1),
+            ]),
+            [vec!["JavaScript", "javascript", "javascrpt"]]
+        );
+    }
+
+    #[test]
+    fn default_fields_skip_free_text() {
+        let mut enumerable = HashSet::new();
+        let fields = vec![
+            FrontmatterField {
+                title: "tags".to_string(),
+                field_type: "string[]".to_string(),
+                ..Default::default()
+            },
+            FrontmatterField {
+                title: "author".to_string(),
+                field_type: "object".to_string(),
+                fields: vec![FrontmatterField {
+                    title: "name".to_string(),
+                    field_type: "string".to_string(),
+                    allowed_values: vec!["Ann".to_string()],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            FrontmatterField {
+                title: "title".to_string(),
+                field_type: "string".to_string(),
+                ..Default::default()
+            },
+        ];
+        enumerable_fields(&fields, "", &mut enumerable);
+        let mut enumerable: Vec<String> = enumerable.into_iter().collect();
+        enumerable.sort();
+        assert_eq!(enumerable, ["author.name", "tags"]);
+
+        assert!(has_shared_values(&values(&[
+            ("draft", 4),
+            ("published", 2)
+        ])));
+        assert!(!has_shared_values(&values(&[("A", 2), ("B", 1), ("C", 1)])));
+    }
+}
@@ -1,5 +1,7 @@
+use super::workspace_index::notify_path_changed;
 use serde::Deserialize;
 use std::path::PathBuf;
+use tauri::AppHandle;
 
 #[derive(Debug, Deserialize)]
 pub struct CreateFileRequest {
@@ -24,7 +26,7 @@ pub struct DeleteItemRequest {
 
 /// Tauri 命令：创建新文件（异步方式）
 #[tauri::command]
-pub async fn create_file(request: CreateFileRequest) -> Result<(), String> {
+pub async fn create_file(app: AppHandle, request: CreateFileRequest) -> Result<(), String> {
     let result = tauri::async_runtime::spawn_blocking(move || {
         let path = PathBuf::from(&request.path);
 
@@ -42,6 +44,7 @@ pub async fn create_file(request: CreateFileRequest) -> Result<(), String> {
         match std::fs::File::create(&path) {
             Ok(_) => {
                 println!("File created successfully: {}", path.display());
+                notify_path_changed(&app, &path);
                 Ok(())
             }
             Err(e) => Err(format!("Failed to create file {}: {}", path.display(), e)),
@@ -55,7 +58,7 @@ pub async fn create_file(request: CreateFileRequest) -> Result<(), String> {
 
 /// Tauri 命令：创建新文件夹（异步方式）
 #[tauri::command]
-pub async fn create_folder(request: CreateFolderRequest) -> Result<(), String> {
+pub async fn create_folder(app: AppHandle, request: CreateFolderRequest) -> Result<(), String> {
     let result = tauri::async_runtime::spawn_blocking(move || {
         let path = PathBuf::from(&request.path);
 
@@ -73,6 +76,7 @@ pub async fn create_folder(request: CreateFolderRequest) -> Result<(), String> {
         match std::fs::create_dir_all(&path) {
             Ok(_) => {
                 println!("Folder created successfully: {}", path.display());
+                notify_path_changed(&app, &path);
                 Ok(())
             }
             Err(e) => Err(format!("Failed to create folder {}: {}", path.display(), e)),
@@ -86,7 +90,7 @@ pub async fn create_folder(request: CreateFolderRequest) -> Result<(), String> {
 
 /// Tauri 命令：重命名文件或文件夹（异步方式）
 #[tauri::command]
-pub async fn rename_item(request: RenameItemRequest) -> Result<(), String> {
+pub async fn rename_item(app: AppHandle, request: RenameItemRequest) -> Result<(), String> {
     let result = tauri::async_runtime::spawn_blocking(move || {
         let old_path = PathBuf::from(&request.old_path);
         let new_path = old_path
@@ -112,6 +116,8 @@ pub async fn rename_item(request: RenameItemRequest) -> Result<(), String> {
                     old_path.display(),
                     new_path.display()
                 );
+                notify_path_changed(&app, &old_path);
+                notify_path_changed(&app, &new_path);
                 Ok(())
             }
             Err(e) => Err(format!("Failed to rename: {}", e)),
@@ -125,7 +131,7 @@ pub async fn rename_item(request: RenameItemRequest) -> Result<(), String> {
 
 /// Tauri 命令：删除文件或文件夹（异步方式）
 #[tauri::command]
-pub async fn delete_item(request: DeleteItemRequest) -> Result<(), String> {
+pub async fn delete_item(app: AppHandle, request: DeleteItemRequest) -> Result<(), String> {
     let result = tauri::async_runtime::spawn_blocking(move || {
         let path = PathBuf::from(&request.path);
 
@@ -143,6 +149,7 @@ pub async fn delete_item(request: DeleteItemRequest) -> Result<(), String> {
             match std::fs::remove_dir_all(&path) {
                 Ok(_) => {
                     println!("Folder deleted successfully: {}", path.display());
+                    notify_path_changed(&app, &path);
                     Ok(())
                 }
                 Err(e) => Err(format!("Failed to delete folder: {}", e)),
@@ -152,6 +159,7 @@ pub async fn delete_item(request: DeleteItemRequest) -> Result<(), String> {
             match std::fs::remove_file(&path) {
                 Ok(_) => {
                     println!("File deleted successfully: {}", path.display());
+                    notify_path_changed(&app, &path);
                     Ok(())
                 }
                 Err(e) => Err(format!("Failed to delete file: {}", e)),
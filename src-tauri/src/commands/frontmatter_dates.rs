@@ -0,0 +1,353 @@
+use super::save_frontmatter::{json_text, FrontmatterField};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone};
+
+/// 带时区的日期时间格式（Jekyll 的 `YYYY-MM-DD HH:MM:SS +0800` 等）
+const OFFSET_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f%z",
+    "%Y-%m-%d %H:%M:%S%.f%:z",
+    "%Y-%m-%d %H:%M:%S%.f %z",
+    "%Y-%m-%d %H:%M:%S%.f %:z",
+    "%Y-%m-%dT%H:%M%:z",
+    "%Y-%m-%d %H:%M %z",
+    "%Y-%m-%d %H:%M %:z",
+];
+
+/// 不带时区的日期时间格式（Hexo 的 `YYYY-MM-DD HH:mm:ss` 等）
+const NAIVE_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+    "%Y/%m/%d %H:%M:%S",
+    "%Y/%m/%d %H:%M",
+];
+
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%Y/%m/%d", "%Y.%m.%d"];
+
+const TIME_FORMATS: &[&str] = &["%H:%M:%S%.f", "%H:%M"];
+
+/// 解析出的日期或时间
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ParsedDate {
+    Offset(DateTime<FixedOffset>),
+    Naive(NaiveDateTime),
+    Date(NaiveDate),
+    Time(NaiveTime),
+}
+
+impl ParsedDate {
+    /// 用于排序与比较的时间点：带时区的值换算为 UTC，只有日期时取当天零点；
+    /// 单独的时间没有对应的时间点，返回 None
+    pub(crate) fn instant(&self) -> Option<NaiveDateTime> {
+        match self {
+            ParsedDate::Offset(dt) => Some(dt.naive_utc()),
+            ParsedDate::Naive(dt) => Some(*dt),
+            ParsedDate::Date(date) => date.and_hms_opt(0, 0, 0),
+            ParsedDate::Time(_) => None,
+        }
+    }
+}
+
+/// 按常见写法解析日期或时间：ISO 8601、RFC 3339、RFC 2822、`YYYY-MM-DD HH:mm`
+/// 以及 Hexo、Jekyll 风格的写法
+pub(crate) fn parse_date(s: &str) -> Option<ParsedDate> {
+    let s = s.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(ParsedDate::Offset(dt));
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc2822(s) {
+        return Some(ParsedDate::Offset(dt));
+    }
+    if let Some(dt) = OFFSET_FORMATS
+        .iter()
+        .find_map(|f| DateTime::parse_from_str(s, f).ok())
+    {
+        return Some(ParsedDate::Offset(dt));
+    }
+    if let Some(dt) = NAIVE_FORMATS
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(s, f).ok())
+    {
+        return Some(ParsedDate::Naive(dt));
+    }
+    if let Some(date) = DATE_FORMATS
+        .iter()
+        .find_map(|f| NaiveDate::parse_from_str(s, f).ok())
+    {
+        return Some(ParsedDate::Date(date));
+    }
+    TIME_FORMATS
+        .iter()
+        .find_map(|f| NaiveTime::parse_from_str(s, f).ok())
+        .map(ParsedDate::Time)
+}
+
+/// 解析时区偏移：`Z`、`UTC`、`+08:00`、`+0800` 或 `+08`
+pub(crate) fn parse_offset(s: &str) -> Option<FixedOffset> {
+    let s = s.trim();
+    if s.eq_ignore_ascii_case("z") || s.eq_ignore_ascii_case("utc") {
+        return FixedOffset::east_opt(0);
+    }
+    let sign = match s.get(..1)? {
+        "+" => 1,
+        "-" => -1,
+        _ => return None,
+    };
+    let digits = s[1..].replace(':', "");
+    if digits.len() != 2 && digits.len() != 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i32 = digits[..2].parse().ok()?;
+    let minutes: i32 = digits
+        .get(2..)
+        .filter(|m| !m.is_empty())
+        .map_or(Ok(0), str::parse)
+        .ok()?;
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// 字段未设置 `date_format` 时使用的输出格式
+fn default_format(field_type: &str) -> &'static str {
+    match field_type {
+        "date" => "%Y-%m-%d",
+        "time" => "%H:%M:%S",
+        _ => "%Y-%m-%dT%H:%M:%S%:z",
+    }
+}
+
+/// 字段配置的输出格式，格式串无效时返回错误
+fn output_format(field: &FrontmatterField) -> Result<&str, String> {
+    let format = field
+        .date_format
+        .as_deref()
+        .filter(|f| !f.trim().is_empty())
+        .unwrap_or_else(|| default_format(&field.field_type));
+    if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+        return Err(format!(
+            "Invalid date format \"{}\" for \"{}\"",
+            format,
+            field.display_name()
+        ));
+    }
+    Ok(format)
+}
+
+/// 字段配置的时区；未配置时为 None，不带时区的值按本地时区处理
+fn field_offset(field: &FrontmatterField) -> Result<Option<FixedOffset>, String> {
+    match field.timezone.as_deref().filter(|tz| !tz.trim().is_empty()) {
+        Some(tz) => parse_offset(tz).map(Some).ok_or_else(|| {
+            format!(
+                "Invalid timezone \"{}\" for \"{}\"",
+                tz,
+                field.display_name()
+            )
+        }),
+        None => Ok(None),
+    }
+}
+
+/// 检查字段的 `date_format` 与 `timezone` 配置是否有效
+pub(crate) fn check_date_settings(field: &FrontmatterField) -> Result<(), String> {
+    output_format(field)?;
+    field_offset(field)?;
+    Ok(())
+}
+
+/// 为不带时区的日期时间补上时区
+fn attach_offset(dt: NaiveDateTime, offset: Option<FixedOffset>) -> Option<DateTime<FixedOffset>> {
+    match offset {
+        Some(offset) => offset.from_local_datetime(&dt).single(),
+        None => Local
+            .from_local_datetime(&dt)
+            .earliest()
+            .map(|local| local.with_timezone(&local.offset().fix())),
+    }
+}
+
+/// 将表单中的日期值（字符串或日期选择器给出的毫秒时间戳）转换为字段配置的格式与时区
+pub(crate) fn normalize_date(
+    field: &FrontmatterField,
+    value: &serde_json::Value,
+) -> Result<String, String> {
+    let format = output_format(field)?;
+    let offset = field_offset(field)?;
+    let invalid = || {
+        format!(
+            "\"{}\" is not a valid {}",
+            json_text(value).unwrap_or_default(),
+            field.field_type
+        )
+    };
+
+    let parsed = match value {
+        serde_json::Value::Number(n) => {
+            let millis = n.as_f64().filter(|n| n.is_finite()).ok_or_else(invalid)?;
+            let utc = DateTime::from_timestamp_millis(millis as i64).ok_or_else(invalid)?;
+            // 时间戳按字段时区（未配置时为本地时区）解释
+            let offset =
+                offset.unwrap_or_else(|| Local.offset_from_utc_datetime(&utc.naive_utc()).fix());
+            ParsedDate::Offset(utc.with_timezone(&offset))
+        }
+        serde_json::Value::String(s) => parse_date(s).ok_or_else(invalid)?,
+        _ => return Err(invalid()),
+    };
+
+    // 带时区的值在配置了时区时换算到该时区；只取日期或时间部分时，未配置则换算到本地时区
+    let parsed = match (parsed, offset) {
+        (ParsedDate::Offset(dt), Some(offset)) => ParsedDate::Offset(dt.with_timezone(&offset)),
+        (ParsedDate::Offset(dt), None) if field.field_type != "dateandtime" => {
+            let local = dt.with_timezone(&Local);
+            ParsedDate::Offset(local.with_timezone(&local.offset().fix()))
+        }
+        (parsed, _) => parsed,
+    };
+
+    let formatted = match (field.field_type.as_str(), parsed) {
+        ("time", ParsedDate::Time(time)) => time.format(format).to_string(),
+        ("time", ParsedDate::Offset(dt)) => dt.time().format(format).to_string(),
+        ("time", ParsedDate::Naive(dt)) => dt.time().format(format).to_string(),
+        ("time", ParsedDate::Date(_)) => return Err(invalid()),
+        (_, ParsedDate::Time(_)) => return Err(invalid()),
+        ("date", ParsedDate::Date(date)) => date.format(format).to_string(),
+        ("date", ParsedDate::Offset(dt)) => dt.date_naive().format(format).to_string(),
+        ("date", ParsedDate::Naive(dt)) => dt.date().format(format).to_string(),
+        (_, ParsedDate::Offset(dt)) => dt.format(format).to_string(),
+        (_, ParsedDate::Naive(dt)) => attach_offset(dt, offset)
+            .ok_or_else(invalid)?
+            .format(format)
+            .to_string(),
+        (_, ParsedDate::Date(date)) => attach_offset(date.and_time(NaiveTime::MIN), offset)
+            .ok_or_else(invalid)?
+            .format(format)
+            .to_string(),
+    };
+    Ok(formatted)
+}
+
+/// 为 `date_as_string` 的顶层日期字段加上引号，使其在 YAML 中是字符串而不是时间戳
+pub(crate) fn quote_date_strings(
+    yaml: &str,
+    fields: &[FrontmatterField],
+    frontmatter: &std::collections::HashMap<String, serde_json::Value>,
+) -> String {
+    let quoted: Vec<(String, &str)> = fields
+        .iter()
+        .filter(|f| f.date_as_string)
+        .filter(|f| matches!(f.field_type.as_str(), "date" | "time" | "dateandtime"))
+        .filter_map(|f| {
+            let value = frontmatter.get(&f.title)?.as_str()?;
+            Some((format!("{}: {}", f.title, value), value))
+        })
+        .collect();
+    let mut output = String::with_capacity(yaml.len());
+    for line in yaml.lines() {
+        match quoted.iter().find(|(plain, _)| plain == line) {
+            Some((_, value)) => {
+                let key = &line[..line.len() - value.len() - 2];
+                output.push_str(&format!("{}: '{}'", key, value.replace('\'', "''")));
+            }
+            None => output.push_str(line),
+        }
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn date_field(field_type: &str, timezone: &str) -> FrontmatterField {
+        FrontmatterField {
+            title: "date".to_string(),
+            field_type: field_type.to_string(),
+            timezone: Some(timezone.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn offset_date(s: &str) -> String {
+        match parse_date(s) {
+            Some(ParsedDate::Offset(dt)) => dt.to_rfc3339(),
+            other => panic!("{} parsed as {:?}", s, other),
+        }
+    }
+
+    #[test]
+    fn parse_date_reads_common_writings() {
+        assert_eq!(
+            offset_date("2024-01-05T10:00:00+0800"),
+            "2024-01-05T10:00:00+08:00"
+        );
+        assert_eq!(
+            offset_date("2024-01-05 10:00:00 +0800"),
+            "2024-01-05T10:00:00+08:00"
+        );
+        assert_eq!(
+            offset_date("2024-01-05T10:00:00Z"),
+            "2024-01-05T10:00:00+00:00"
+        );
+        assert_eq!(
+            parse_date("2024-01-05 10:00:00"),
+            NaiveDate::from_ymd_opt(2024, 1, 5)
+                .and_then(|d| d.and_hms_opt(10, 0, 0))
+                .map(ParsedDate::Naive)
+        );
+        assert_eq!(
+            parse_date("2024/01/05"),
+            NaiveDate::from_ymd_opt(2024, 1, 5).map(ParsedDate::Date)
+        );
+        assert_eq!(
+            parse_date("10:30"),
+            NaiveTime::from_hms_opt(10, 30, 0).map(ParsedDate::Time)
+        );
+        assert_eq!(parse_date("next tuesday"), None);
+    }
+
+    #[test]
+    fn parse_offset_accepts_short_and_long_forms() {
+        let east8 = FixedOffset::east_opt(8 * 3600);
+        assert_eq!(parse_offset("+08:00"), east8);
+        assert_eq!(parse_offset("+0800"), east8);
+        assert_eq!(parse_offset("+08"), east8);
+        assert_eq!(parse_offset("UTC"), FixedOffset::east_opt(0));
+        assert_eq!(
+            parse_offset("-05:30"),
+            FixedOffset::west_opt(5 * 3600 + 1800)
+        );
+        assert_eq!(parse_offset("+24:00"), None);
+        assert_eq!(parse_offset("0800"), None);
+    }
+
+    #[test]
+    fn normalize_date_converts_to_the_field_timezone() {
+        let field = date_field("dateandtime", "+08:00");
+        assert_eq!(
+            normalize_date(&field, &json!("2024-01-05T02:00:00Z")).unwrap(),
+            "2024-01-05T10:00:00+08:00"
+        );
+        assert_eq!(
+            normalize_date(&field, &json!("2024-01-05 10:00")).unwrap(),
+            "2024-01-05T10:00:00+08:00"
+        );
+        assert_eq!(
+            normalize_date(
+                &date_field("date", "+08:00"),
+                &json!("2024-01-04T20:00:00Z")
+            )
+            .unwrap(),
+            "2024-01-05"
+        );
+        assert_eq!(
+            normalize_date(&date_field("time", "+08:00"), &json!("2024-01-05 09:15")).unwrap(),
+            "09:15:00"
+        );
+        assert!(normalize_date(&date_field("time", "+08:00"), &json!("2024-01-05")).is_err());
+        assert!(normalize_date(&date_field("date", "+25:00"), &json!("2024-01-05")).is_err());
+    }
+}
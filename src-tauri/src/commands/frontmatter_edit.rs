@@ -0,0 +1,690 @@
+use super::search::frontmatter_line_count;
+use std::collections::HashSet;
+use std::ops::Range;
+
+/// 标量在文件中的引号风格
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum QuoteStyle {
+    Plain,
+    Single,
+    Double,
+}
+
+/// 从文件中读出的标量：去掉引号后的值与原始写法
+struct Scalar {
+    value: String,
+    style: QuoteStyle,
+}
+
+fn parse_scalar(raw: &str) -> Scalar {
+    let raw = raw.trim();
+    if raw.len() >= 2 && raw.starts_with('"') && raw.ends_with('"') {
+        return Scalar {
+            value: unescape_double(&raw[1..raw.len() - 1]),
+            style: QuoteStyle::Double,
+        };
+    }
+    if raw.len() >= 2 && raw.starts_with('\'') && raw.ends_with('\'') {
+        return Scalar {
+            value: raw[1..raw.len() - 1].replace("''", "'"),
+            style: QuoteStyle::Single,
+        };
+    }
+    Scalar {
+        value: raw.to_string(),
+        style: QuoteStyle::Plain,
+    }
+}
+
+fn unescape_double(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some('0') => out.push('\0'),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                    Some(decoded) => out.push(decoded),
+                    None => {
+                        out.push_str("\\u");
+                        out.push_str(&hex);
+                    }
+                }
+            }
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+fn quote_double(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// 不加引号时会被 YAML 解析为数字、布尔值或 null 的文本
+fn looks_non_string(value: &str) -> bool {
+    let lower = value.to_lowercase();
+    matches!(
+        lower.as_str(),
+        "true" | "false" | "yes" | "no" | "on" | "off" | "null" | "~"
+    ) || value.parse::<f64>().is_ok()
+}
+
+/// 不加引号会改变含义或破坏语法的文本
+fn needs_quotes(value: &str, in_flow: bool) -> bool {
+    value.is_empty()
+        || value.trim() != value
+        || value.starts_with([
+            '-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%',
+            '@', '`',
+        ])
+        || value.contains(": ")
+        || value.contains(" #")
+        || value.ends_with(':')
+        || value.contains(['\n', '\r', '\t'])
+        || (in_flow && value.contains([',', '[', ']', '{', '}']))
+}
+
+/// 按原值的引号风格写出新值；原来不加引号的字符串在必要时改用双引号。
+fn format_scalar(value: &str, original: &Scalar, in_flow: bool) -> String {
+    match original.style {
+        QuoteStyle::Plain => {
+            let changes_type = looks_non_string(value) && !looks_non_string(&original.value);
+            if needs_quotes(value, in_flow) || changes_type {
+                quote_double(value)
+            } else {
+                value.to_string()
+            }
+        }
+        QuoteStyle::Single if !value.contains(['\n', '\r']) => {
+            format!("'{}'", value.replace('\'', "''"))
+        }
+        _ => quote_double(value),
+    }
+}
+
+/// 将行内容拆分为值与行尾注释（注释部分包含其前面的空白）
+fn split_comment(s: &str) -> (&str, &str) {
+    let mut in_single = false;
+    let mut in_double = false;
+    let mut escaped = false;
+    let mut prev_space = true;
+    for (i, c) in s.char_indices() {
+        if in_double {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_double = false;
+            }
+        } else if in_single {
+            if c == '\'' {
+                in_single = false;
+            }
+        } else if c == '#' && prev_space {
+            let cut = s[..i].trim_end().len();
+            return (&s[..cut], &s[cut..]);
+        } else if c == '"' {
+            in_double = true;
+        } else if c == '\'' {
+            in_single = true;
+        }
+        prev_space = c.is_whitespace();
+    }
+    (s, "")
+}
+
+/// 拆分行内数组 `[a, "b", c]` 的内部文本；包含嵌套结构时返回 None。
+fn split_flow_items(inner: &str) -> Option<Vec<&str>> {
+    let mut items = Vec::new();
+    let mut in_single = false;
+    let mut in_double = false;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, c) in inner.char_indices() {
+        if in_double {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_double = false;
+            }
+            continue;
+        }
+        if in_single {
+            if c == '\'' {
+                in_single = false;
+            }
+            continue;
+        }
+        match c {
+            '"' => in_double = true,
+            '\'' => in_single = true,
+            '[' | ']' | '{' | '}' => return None,
+            ',' => {
+                items.push(inner[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if in_single || in_double {
+        return None;
+    }
+    let last = inner[start..].trim();
+    if !last.is_empty() {
+        items.push(last);
+    }
+    if items.iter().any(|item| item.is_empty()) {
+        return None;
+    }
+    Some(items)
+}
+
+/// 解析顶层键所在的行，返回键名以及冒号之后的字节位置
+fn parse_key_line(line: &str) -> Option<(String, usize)> {
+    let first = line.chars().next()?;
+    if first.is_whitespace() || first == '#' || first == '-' {
+        return None;
+    }
+    if first == '"' || first == '\'' {
+        let close = line[1..].find(first)? + 1;
+        let key = parse_scalar(&line[..=close]).value;
+        let rest = &line[close + 1..];
+        let colon = rest.len() - rest.trim_start().len();
+        if !rest[colon..].starts_with(':') {
+            return None;
+        }
+        return Some((key, close + 1 + colon + 1));
+    }
+    let bytes = line.as_bytes();
+    let colon = (0..bytes.len())
+        .find(|&i| bytes[i] == b':' && bytes.get(i + 1).is_none_or(|b| b.is_ascii_whitespace()))?;
+    Some((line[..colon].trim_end().to_string(), colon + 1))
+}
+
+fn strip_line_ending(line: &str) -> &str {
+    line.strip_suffix('\n')
+        .map(|l| l.strip_suffix('\r').unwrap_or(l))
+        .unwrap_or(line)
+}
+
+fn line_ending(line: &str) -> &str {
+    &line[strip_line_ending(line).len()..]
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn unsupported(key: &str) -> String {
+    format!("Unsupported YAML syntax in field \"{}\"", key)
+}
+
+/// 按行编辑 Markdown 文件的 frontmatter，只改动涉及的行，
+/// 保留其余内容的引号、缩进、注释与数组写法。
+#[derive(Clone)]
+pub(crate) struct FrontmatterLines {
+    /// 文件的所有行（包含换行符）
+    lines: Vec<String>,
+    /// 结束标记 `---` 所在行的下标
+    end: usize,
+}
+
+impl FrontmatterLines {
+    /// 解析文件内容，没有 frontmatter 时返回 None
+    pub(crate) fn parse(content: &str) -> Option<Self> {
+        let count = frontmatter_line_count(content);
+        if count == 0 {
+            return None;
+        }
+        Some(FrontmatterLines {
+            lines: content.split_inclusive('\n').map(String::from).collect(),
+            end: count - 1,
+        })
+    }
+
+    /// 解析文件内容，没有 frontmatter 时在文件开头加上空的 frontmatter
+    pub(crate) fn parse_or_new(content: &str) -> Self {
+        Self::parse(content).unwrap_or_else(|| {
+            let mut lines = vec!["---\n".to_string(), "---\n".to_string()];
+            lines.extend(content.split_inclusive('\n').map(String::from));
+            FrontmatterLines { lines, end: 1 }
+        })
+    }
+
+    pub(crate) fn into_content(self) -> String {
+        self.lines.concat()
+    }
+
+    fn text(&self, index: usize) -> &str {
+        strip_line_ending(&self.lines[index])
+    }
+
+    /// frontmatter 的 YAML 文本（不含首尾的 `---`），从文件第 2 行开始
+    pub(crate) fn yaml(&self) -> String {
+        self.lines[1..self.end].concat()
+    }
+
+    /// 顶层键及其所在的行号（从 1 开始），按出现顺序，重复的键会出现多次
+    pub(crate) fn keys(&self) -> Vec<(String, usize)> {
+        (1..self.end)
+            .filter_map(|i| parse_key_line(self.text(i)).map(|(key, _)| (key, i + 1)))
+            .collect()
+    }
+
+    /// 顶层键 `key` 所在的行号（从 1 开始）
+    pub(crate) fn key_line(&self, key: &str) -> Option<usize> {
+        self.field_range(key).map(|range| range.start + 1)
+    }
+
+    /// 字段占用的行：键所在行以及其后缩进的行或 `-` 开头的数组项
+    fn field_range(&self, key: &str) -> Option<Range<usize>> {
+        let start = (1..self.end)
+            .find(|&i| parse_key_line(self.text(i)).is_some_and(|(found, _)| found == key))?;
+        let mut last = start;
+        for i in start + 1..self.end {
+            let line = self.text(i);
+            if line.trim().is_empty() {
+                continue;
+            }
+            if line.starts_with([' ', '\t', '-']) {
+                last = i;
+            } else {
+                break;
+            }
+        }
+        Some(start..last + 1)
+    }
+
+    /// 删除顶层字段，返回字段是否存在
+    pub(crate) fn remove_field(&mut self, key: &str) -> bool {
+        match self.field_range(key) {
+            Some(range) => {
+                self.end -= range.len();
+                self.lines.drain(range);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 新写入的行使用的换行符，与 frontmatter 开头一致
+    fn newline(&self) -> String {
+        match line_ending(&self.lines[0]) {
+            "" => "\n".to_string(),
+            ending => ending.to_string(),
+        }
+    }
+
+    /// 字段当前的值；字段不存在或无法解析时返回 None
+    fn parsed_value(&self, key: &str) -> Option<serde_yaml::Value> {
+        let range = self.field_range(key)?;
+        let mut mapping: serde_yaml::Mapping =
+            serde_yaml::from_str(&self.lines[range].concat()).ok()?;
+        mapping.remove(key)
+    }
+
+    /// 将顶层字段设为 `value`：字段存在时替换其所占的行（单行值保留行尾注释），
+    /// 不存在时添加到 frontmatter 末尾。返回文件是否被修改。
+    pub(crate) fn set_field(
+        &mut self,
+        key: &str,
+        value: &serde_yaml::Value,
+    ) -> Result<bool, String> {
+        let range = self.field_range(key);
+        if range.is_some() && self.parsed_value(key).as_ref() == Some(value) {
+            return Ok(false);
+        }
+
+        let mut mapping = serde_yaml::Mapping::new();
+        mapping.insert(serde_yaml::Value::String(key.to_string()), value.clone());
+        let yaml = serde_yaml::to_string(&mapping)
+            .map_err(|e| format!("YAML serialization error: {}", e))?;
+        let newline = self.newline();
+        let mut rendered: Vec<String> = yaml
+            .lines()
+            .map(|line| format!("{}{}", line, newline))
+            .collect();
+
+        match range {
+            Some(range) => {
+                let line = self.text(range.start).to_string();
+                if let (1, Some((_, value_start))) = (rendered.len(), parse_key_line(&line)) {
+                    let (_, comment) = split_comment(&line[value_start..]);
+                    if !comment.is_empty() {
+                        rendered[0] = format!("{}{}{}", yaml.trim_end(), comment, newline);
+                    }
+                }
+                self.end = self.end - range.len() + rendered.len();
+                self.lines.splice(range, rendered);
+            }
+            None => {
+                let at = self.end;
+                self.end += rendered.len();
+                self.lines.splice(at..at, rendered);
+            }
+        }
+        Ok(true)
+    }
+
+    /// 向数组字段末尾追加 `values` 中尚不存在的值，沿用原有的行内或块数组写法；
+    /// 单个标量会改写为行内数组，字段不存在时新建。返回文件是否被修改。
+    pub(crate) fn append_values(&mut self, key: &str, values: &[String]) -> Result<bool, String> {
+        let Some(range) = self.field_range(key) else {
+            let mut seen = HashSet::new();
+            let items = values
+                .iter()
+                .filter(|v| seen.insert(v.as_str()))
+                .map(|v| serde_yaml::Value::String(v.clone()))
+                .collect();
+            return self.set_field(key, &serde_yaml::Value::Sequence(items));
+        };
+        let mut seen: HashSet<String> = self
+            .values(key)
+            .ok_or_else(|| unsupported(key))?
+            .into_iter()
+            .collect();
+        let added: Vec<&String> = values
+            .iter()
+            .filter(|v| seen.insert(v.to_string()))
+            .collect();
+        if added.is_empty() {
+            return Ok(false);
+        }
+        let plain = Scalar {
+            value: String::new(),
+            style: QuoteStyle::Plain,
+        };
+
+        let key_index = range.start;
+        let line = self.text(key_index).to_string();
+        let ending = line_ending(&self.lines[key_index]).to_string();
+        let (_, value_start) = parse_key_line(&line).ok_or_else(|| unsupported(key))?;
+        let (value_part, comment) = split_comment(&line[value_start..]);
+        let value = value_part.trim();
+
+        if value.is_empty() {
+            let item =
+                (key_index + 1..range.end).find(|&i| self.text(i).trim_start().starts_with('-'));
+            match item {
+                // 块数组：按第一项的缩进在最后一项之后添加
+                Some(first) => {
+                    let first_line = self.text(first);
+                    let prefix = first_line[..indent_of(first_line)].to_string();
+                    let newline = self.newline();
+                    let lines: Vec<String> = added
+                        .iter()
+                        .map(|v| {
+                            format!("{}- {}{}", prefix, format_scalar(v, &plain, false), newline)
+                        })
+                        .collect();
+                    self.end += lines.len();
+                    self.lines.splice(range.end..range.end, lines);
+                }
+                // 值为空（null）：改写为行内数组
+                None => {
+                    if (key_index + 1..range.end).any(|i| !self.text(i).trim().is_empty()) {
+                        return Err(unsupported(key));
+                    }
+                    let flow: Vec<String> = added
+                        .iter()
+                        .map(|v| format_scalar(v, &plain, true))
+                        .collect();
+                    self.lines[key_index] = format!(
+                        "{} [{}]{}{}",
+                        &line[..value_start],
+                        flow.join(", "),
+                        comment,
+                        ending
+                    );
+                }
+            }
+            return Ok(true);
+        }
+
+        let head = &line[..value_start + (value_part.len() - value_part.trim_start().len())];
+        let flow = match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            Some(inner) => {
+                let items = split_flow_items(inner).ok_or_else(|| unsupported(key))?;
+                let separator = if inner.contains(", ") || items.len() < 2 {
+                    ", "
+                } else {
+                    ","
+                };
+                let padding = if inner.starts_with(' ') { " " } else { "" };
+                let items = items
+                    .iter()
+                    .map(|raw| raw.to_string())
+                    .chain(added.iter().map(|v| format_scalar(v, &plain, true)));
+                format!(
+                    "[{0}{1}{0}]",
+                    padding,
+                    items.collect::<Vec<_>>().join(separator)
+                )
+            }
+            // 单个标量：与追加的值合并为行内数组
+            None => {
+                let scalar = parse_scalar(value);
+                let items = std::iter::once(format_scalar(&scalar.value, &scalar, true))
+                    .chain(added.iter().map(|v| format_scalar(v, &plain, true)));
+                format!("[{}]", items.collect::<Vec<_>>().join(", "))
+            }
+        };
+        self.lines[key_index] = format!("{}{}{}{}", head, flow, comment, ending);
+        Ok(true)
+    }
+
+    /// 字段中的所有标量值；字段不存在或无法解析时返回 None
+    pub(crate) fn values(&self, key: &str) -> Option<Vec<String>> {
+        let mut values = Vec::new();
+        self.clone()
+            .map_values(key, |value| {
+                values.push(value.to_string());
+                Some(value.to_string())
+            })
+            .ok()?;
+        Some(values)
+    }
+
+    /// 对字段 `key` 的每个标量值调用 `f`：返回 None 删除该值，返回 Some 替换为新值。
+    /// 数组中替换后重复的值只保留第一个；单个标量被删除时删除整个字段。
+    /// 返回文件是否被修改，遇到无法安全改写的写法时返回错误。
+    pub(crate) fn map_values(
+        &mut self,
+        key: &str,
+        mut f: impl FnMut(&str) -> Option<String>,
+    ) -> Result<bool, String> {
+        let Some(range) = self.field_range(key) else {
+            return Ok(false);
+        };
+        let key_index = range.start;
+        let line = self.text(key_index).to_string();
+        let ending = line_ending(&self.lines[key_index]).to_string();
+        let (_, value_start) = parse_key_line(&line).ok_or_else(|| unsupported(key))?;
+        let (value_part, comment) = split_comment(&line[value_start..]);
+        let value = value_part.trim();
+        let head = &line[..value_start + (value_part.len() - value_part.trim_start().len())];
+
+        if value.is_empty() {
+            return self.map_block_items(key, range, &mut f);
+        }
+        if range.len() > 1 {
+            // 多行标量或写在键后的嵌套结构
+            return Err(unsupported(key));
+        }
+
+        if let Some(inner) = value.strip_prefix('[') {
+            let inner = inner.strip_suffix(']').ok_or_else(|| unsupported(key))?;
+            let items = split_flow_items(inner).ok_or_else(|| unsupported(key))?;
+            let mut seen = HashSet::new();
+            let mut changed = false;
+            let mut rewritten = Vec::new();
+            for raw in &items {
+                let scalar = parse_scalar(raw);
+                match f(&scalar.value) {
+                    Some(new) if seen.insert(new.clone()) => {
+                        if new == scalar.value {
+                            rewritten.push(raw.to_string());
+                        } else {
+                            rewritten.push(format_scalar(&new, &scalar, true));
+                            changed = true;
+                        }
+                    }
+                    _ => changed = true,
+                }
+            }
+            if !changed {
+                return Ok(false);
+            }
+            let separator = if inner.contains(", ") || items.len() < 2 {
+                ", "
+            } else {
+                ","
+            };
+            let padding = if inner.starts_with(' ') { " " } else { "" };
+            let flow = if rewritten.is_empty() {
+                "[]".to_string()
+            } else {
+                format!("[{0}{1}{0}]", padding, rewritten.join(separator))
+            };
+            self.lines[key_index] = format!("{}{}{}{}", head, flow, comment, ending);
+            return Ok(true);
+        }
+
+        if value.starts_with(['{', '|', '>', '&', '*', '!']) {
+            return Err(unsupported(key));
+        }
+        let scalar = parse_scalar(value);
+        match f(&scalar.value) {
+            None => {
+                self.remove_field(key);
+                Ok(true)
+            }
+            Some(new) if new == scalar.value => Ok(false),
+            Some(new) => {
+                self.lines[key_index] = format!(
+                    "{}{}{}{}",
+                    head,
+                    format_scalar(&new, &scalar, false),
+                    comment,
+                    ending
+                );
+                Ok(true)
+            }
+        }
+    }
+
+    /// 改写块数组（每行一个 `- value`）中的标量项，跳过包含嵌套内容的项
+    fn map_block_items(
+        &mut self,
+        key: &str,
+        range: Range<usize>,
+        f: &mut impl FnMut(&str) -> Option<String>,
+    ) -> Result<bool, String> {
+        let is_item = |line: &str| {
+            let trimmed = line.trim_start();
+            trimmed == "-" || trimmed.starts_with("- ") || trimmed.starts_with("-\t")
+        };
+        let Some(first) = (range.start + 1..range.end).find(|&i| is_item(self.text(i))) else {
+            return Ok(false);
+        };
+        let item_indent = indent_of(self.text(first));
+        if (range.start + 1..first).any(|i| !self.text(i).trim().is_empty()) {
+            return Err(unsupported(key));
+        }
+        let starts: Vec<usize> = (first..range.end)
+            .filter(|&i| is_item(self.text(i)) && indent_of(self.text(i)) == item_indent)
+            .collect();
+
+        let mut rewritten: Vec<String> = Vec::new();
+        let mut seen = HashSet::new();
+        let mut changed = false;
+        let mut kept_items = 0;
+        for (n, &start) in starts.iter().enumerate() {
+            let end = starts.get(n + 1).copied().unwrap_or(range.end);
+            let owned = &self.lines[start..end];
+            let line = self.text(start);
+            let after_dash = &line.trim_start()[1..];
+            let (item, comment) = split_comment(after_dash);
+            let simple = owned[1..].iter().all(|l| l.trim().is_empty())
+                && !item.trim().is_empty()
+                && !item
+                    .trim_start()
+                    .starts_with(['[', '{', '|', '>', '&', '*', '!', '-'])
+                && (parse_scalar(item).style != QuoteStyle::Plain || !item.contains(": "));
+            if !simple {
+                rewritten.extend(owned.iter().cloned());
+                kept_items += 1;
+                continue;
+            }
+
+            let scalar = parse_scalar(item);
+            match f(&scalar.value) {
+                Some(new) if seen.insert(new.clone()) => {
+                    kept_items += 1;
+                    if new == scalar.value {
+                        rewritten.extend(owned.iter().cloned());
+                    } else {
+                        let prefix_len =
+                            line.len() - after_dash.len() + (item.len() - item.trim_start().len());
+                        rewritten.push(format!(
+                            "{}{}{}{}",
+                            &line[..prefix_len],
+                            format_scalar(&new, &scalar, false),
+                            comment,
+                            line_ending(&owned[0])
+                        ));
+                        rewritten.extend(owned[1..].iter().cloned());
+                        changed = true;
+                    }
+                }
+                _ => {
+                    // 保留被删除项之后的空行
+                    rewritten.extend(owned[1..].iter().cloned());
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            return Ok(false);
+        }
+
+        if kept_items == 0 {
+            let line = self.text(range.start).to_string();
+            let ending = line_ending(&self.lines[range.start]).to_string();
+            let (_, value_start) = parse_key_line(&line).ok_or_else(|| unsupported(key))?;
+            let (_, comment) = split_comment(&line[value_start..]);
+            self.lines[range.start] = format!("{} []{}{}", &line[..value_start], comment, ending);
+        }
+        let removed = range.end - first;
+        self.end = self.end - removed + rewritten.len();
+        self.lines.splice(first..range.end, rewritten);
+        Ok(true)
+    }
+}
@@ -0,0 +1,547 @@
+use super::frontmatter_dates::parse_date;
+use super::frontmatter_edit::FrontmatterLines;
+use super::save_frontmatter::FrontmatterField;
+use super::workspace_config::{load_workspace_config, require_workspace};
+use super::workspace_index::{markdown_files, markdown_frontmatter, scalar_value, Frontmatter};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Window};
+
+/// 用于检查重复的 slug 字段
+const SLUG_FIELD: &str = "slug";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+/// 单条检查结果
+#[derive(Debug, Serialize)]
+pub struct FrontmatterDiagnostic {
+    pub path: String,
+    /// 相关的字段，针对整个文件的问题（如 YAML 语法错误）为 None
+    pub field: Option<String>,
+    /// 行号（从 1 开始）；字段缺失时指向 frontmatter 的第一行
+    pub line: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// 单个文件的检查上下文
+struct FileLint<'a> {
+    path: &'a str,
+    lines: Option<FrontmatterLines>,
+    diagnostics: Vec<FrontmatterDiagnostic>,
+}
+
+impl FileLint<'_> {
+    fn push(&mut self, field: Option<&str>, severity: Severity, message: String) {
+        // 嵌套字段（`cover.alt`）指向其顶层键所在的行
+        let line = field
+            .and_then(|f| f.split('.').next())
+            .and_then(|f| self.lines.as_ref().and_then(|l| l.key_line(f)))
+            .unwrap_or(1);
+        self.diagnostics.push(FrontmatterDiagnostic {
+            path: self.path.to_string(),
+            field: field.map(|f| f.to_string()),
+            line,
+            severity,
+            message,
+        });
+    }
+}
+
+fn digits(s: &str, range: std::ops::Range<usize>) -> Option<u32> {
+    let part = s.get(range)?;
+    if part.bytes().all(|b| b.is_ascii_digit()) {
+        part.parse().ok()
+    } else {
+        None
+    }
+}
+
+/// `YYYY-MM-DD`，并检查月份与当月天数
+pub(crate) fn is_valid_date(s: &str) -> bool {
+    if s.len() != 10 || s.get(4..5) != Some("-") || s.get(7..8) != Some("-") {
+        return false;
+    }
+    let (Some(year), Some(month), Some(day)) = (digits(s, 0..4), digits(s, 5..7), digits(s, 8..10))
+    else {
+        return false;
+    };
+    let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days).contains(&day)
+}
+
+/// `HH:MM`、`HH:MM:SS` 或带小数秒的 `HH:MM:SS.fff`
+pub(crate) fn is_valid_time(s: &str) -> bool {
+    let (Some(hour), Some(minute)) = (digits(s, 0..2), digits(s, 3..5)) else {
+        return false;
+    };
+    if s.get(2..3) != Some(":") || hour > 23 || minute > 59 {
+        return false;
+    }
+    match s.get(5..) {
+        Some("") => true,
+        Some(rest) => {
+            let Some(seconds) = rest.strip_prefix(':') else {
+                return false;
+            };
+            let (whole, fraction) = seconds.split_once('.').unwrap_or((seconds, "0"));
+            whole.len() == 2
+                && digits(whole, 0..2).is_some_and(|s| s <= 60)
+                && !fraction.is_empty()
+                && fraction.bytes().all(|b| b.is_ascii_digit())
+        }
+        None => false,
+    }
+}
+
+/// 日期与时间之间用 `T` 或空格分隔，时区可以是 `Z`、`+08:00` 或 `+0800`
+pub(crate) fn is_valid_datetime(s: &str) -> bool {
+    let Some(date) = s.get(..10) else {
+        return false;
+    };
+    if !is_valid_date(date) || !matches!(s.get(10..11), Some("T" | "t" | " ")) {
+        return false;
+    }
+    let rest = &s[11..];
+    let (time, zone) = match rest.find(['Z', 'z', '+', '-']) {
+        Some(i) => (rest[..i].trim_end(), &rest[i..]),
+        None => (rest, ""),
+    };
+    let zone_ok = match zone {
+        "" | "Z" | "z" => true,
+        _ => {
+            let offset = zone[1..].replace(':', "");
+            offset.len() == 4
+                && digits(&offset, 0..2).is_some_and(|h| h <= 23)
+                && digits(&offset, 2..4).is_some_and(|m| m <= 59)
+        }
+    };
+    is_valid_time(time) && zone_ok
+}
+
+fn describe(value: &serde_yaml::Value) -> &'static str {
+    match value {
+        serde_yaml::Value::Null => "null",
+        serde_yaml::Value::Bool(_) => "a boolean",
+        serde_yaml::Value::Number(_) => "a number",
+        serde_yaml::Value::String(_) => "a string",
+        serde_yaml::Value::Sequence(_) => "an array",
+        serde_yaml::Value::Mapping(_) => "an object",
+        serde_yaml::Value::Tagged(tagged) => describe(&tagged.value),
+    }
+}
+
+/// 检查值是否符合字段类型，`key` 为字段的完整路径（嵌套字段形如 `cover.alt`）
+fn check_type(lint: &mut FileLint, key: &str, field: &FrontmatterField, value: &serde_yaml::Value) {
+    let expected = |lint: &mut FileLint, severity, what: &str| {
+        lint.push(
+            Some(key),
+            severity,
+            format!("Expected {}, found {}", what, describe(value)),
+        );
+    };
+    match field.field_type.as_str() {
+        "string" => match value {
+            serde_yaml::Value::String(_) => {}
+            serde_yaml::Value::Sequence(items)
+                if field.save_as_array && items.len() == 1 && items[0].is_string() => {}
+            serde_yaml::Value::Number(_) | serde_yaml::Value::Bool(_) => {
+                expected(lint, Severity::Warning, "a string")
+            }
+            _ => expected(lint, Severity::Error, "a string"),
+        },
+        "string[]" => match value {
+            serde_yaml::Value::Sequence(items) => {
+                if items.iter().any(|item| scalar_value(item).is_none()) {
+                    lint.push(
+                        Some(key),
+                        Severity::Error,
+                        "Array items must be plain values".to_string(),
+                    );
+                }
+            }
+            serde_yaml::Value::String(_) => expected(lint, Severity::Warning, "an array"),
+            _ => expected(lint, Severity::Error, "an array"),
+        },
+        "boolean" => match value {
+            serde_yaml::Value::Bool(_) => {}
+            serde_yaml::Value::String(s) if matches!(s.as_str(), "true" | "false") => {
+                expected(lint, Severity::Warning, "a boolean")
+            }
+            _ => expected(lint, Severity::Error, "a boolean"),
+        },
+        "number[]" => match value {
+            serde_yaml::Value::Sequence(items) => {
+                let whole = |item: &serde_yaml::Value| item.is_i64() || item.is_u64();
+                if items.iter().any(|item| !item.is_number()) {
+                    lint.push(
+                        Some(key),
+                        Severity::Error,
+                        "Array items must be numbers".to_string(),
+                    );
+                } else if field.integer && !items.iter().all(whole) {
+                    lint.push(
+                        Some(key),
+                        Severity::Error,
+                        "Array items must be whole numbers".to_string(),
+                    );
+                }
+            }
+            _ => expected(lint, Severity::Error, "an array of numbers"),
+        },
+        "object" => match value {
+            serde_yaml::Value::Mapping(mapping) => check_nested(lint, key, field, mapping),
+            _ => expected(lint, Severity::Error, "an object"),
+        },
+        "object[]" => match value {
+            serde_yaml::Value::Sequence(items) => {
+                for item in items {
+                    match item {
+                        serde_yaml::Value::Mapping(mapping) => {
+                            check_nested(lint, key, field, mapping)
+                        }
+                        _ => lint.push(
+                            Some(key),
+                            Severity::Error,
+                            format!("Expected an object in the array, found {}", describe(item)),
+                        ),
+                    }
+                }
+            }
+            _ => expected(lint, Severity::Error, "an array of objects"),
+        },
+        "number" => match value {
+            serde_yaml::Value::Number(n) if field.integer && !(n.is_i64() || n.is_u64()) => {
+                expected(lint, Severity::Error, "a whole number")
+            }
+            serde_yaml::Value::Number(n) if n.as_f64().is_some_and(|n| !n.is_finite()) => {
+                expected(lint, Severity::Error, "a finite number")
+            }
+            serde_yaml::Value::Number(_) => {}
+            serde_yaml::Value::String(s) if s.trim().parse::<f64>().is_ok() => {
+                expected(lint, Severity::Warning, "a number")
+            }
+            _ => expected(lint, Severity::Error, "a number"),
+        },
+        "date" | "time" | "dateandtime" => {
+            let Some(text) = value.as_str() else {
+                expected(lint, Severity::Error, "a date string");
+                return;
+            };
+            let valid = match field.field_type.as_str() {
+                "date" => is_valid_date(text),
+                "time" => is_valid_time(text),
+                _ => is_valid_datetime(text),
+            };
+            if valid {
+                return;
+            }
+            if field.field_type == "dateandtime" && is_valid_date(text) {
+                lint.push(
+                    Some(key),
+                    Severity::Warning,
+                    format!("\"{}\" has no time component", text),
+                );
+            } else if parse_date(text).is_some() {
+                lint.push(
+                    Some(key),
+                    Severity::Warning,
+                    format!(
+                        "\"{}\" is not a standard {} and may be read differently by site generators",
+                        text, field.field_type
+                    ),
+                );
+            } else {
+                lint.push(
+                    Some(key),
+                    Severity::Error,
+                    format!("Cannot parse \"{}\" as {}", text, field.field_type),
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 按 `object` / `object[]` 字段的子字段定义检查嵌套映射
+fn check_nested(
+    lint: &mut FileLint,
+    key: &str,
+    field: &FrontmatterField,
+    mapping: &serde_yaml::Mapping,
+) {
+    for nested in &field.fields {
+        let path = format!("{}.{}", key, nested.title);
+        match mapping.get(nested.title.as_str()).filter(|v| !v.is_null()) {
+            Some(value) => {
+                check_type(lint, &path, nested, value);
+                check_constraints(lint, &path, nested, value);
+            }
+            None if nested.required => lint.push(
+                Some(&path),
+                Severity::Error,
+                format!("Missing required field \"{}\"", path),
+            ),
+            None => {}
+        }
+    }
+}
+
+/// 检查允许值、数值范围与正则约束
+fn check_constraints(
+    lint: &mut FileLint,
+    key: &str,
+    field: &FrontmatterField,
+    value: &serde_yaml::Value,
+) {
+    let values: Vec<(String, &'static str)> = match value {
+        serde_yaml::Value::Sequence(items) => items.iter().filter_map(scalar_value).collect(),
+        _ => scalar_value(value).into_iter().collect(),
+    };
+
+    if !field.allowed_values.is_empty() {
+        for (value, _) in &values {
+            if !field.allowed_values.contains(value) {
+                lint.push(
+                    Some(key),
+                    Severity::Error,
+                    format!("\"{}\" is not one of the allowed values", value),
+                );
+            }
+        }
+    }
+
+    if field.field_type == "number" || field.field_type == "number[]" {
+        for number in values.iter().filter_map(|(v, _)| v.parse::<f64>().ok()) {
+            if field.min.is_some_and(|min| number < min)
+                || field.max.is_some_and(|max| number > max)
+            {
+                lint.push(
+                    Some(key),
+                    Severity::Error,
+                    format!("{} is out of the allowed range", number),
+                );
+            }
+        }
+    }
+
+    let Some(pattern) = field.pattern.as_deref().filter(|p| !p.is_empty()) else {
+        return;
+    };
+    if field.field_type != "string" && field.field_type != "string[]" {
+        return;
+    }
+    match regex::Regex::new(pattern) {
+        Ok(regex) => {
+            for (value, _) in values.iter().filter(|(v, _)| !regex.is_match(v)) {
+                lint.push(
+                    Some(key),
+                    Severity::Error,
+                    format!("\"{}\" does not match the pattern {}", value, pattern),
+                );
+            }
+        }
+        Err(e) => lint.push(
+            Some(key),
+            Severity::Warning,
+            format!("Invalid pattern in schema: {}", e),
+        ),
+    }
+}
+
+/// 解析 frontmatter，YAML 有误时返回错误信息与文件中的行号
+fn parse_frontmatter(lines: &FrontmatterLines) -> Result<Frontmatter, (String, usize)> {
+    let yaml = lines.yaml();
+    if yaml.trim().is_empty() {
+        return Ok(Frontmatter::new());
+    }
+    match serde_yaml::from_str::<serde_yaml::Value>(&yaml) {
+        Ok(serde_yaml::Value::Mapping(mapping)) => Ok(mapping
+            .into_iter()
+            .filter_map(|(k, v)| k.as_str().map(|k| (k.to_string(), v)))
+            .collect()),
+        Ok(serde_yaml::Value::Null) => Ok(Frontmatter::new()),
+        Ok(_) => Err(("Frontmatter must be a mapping".to_string(), 2)),
+        // frontmatter 从文件第 2 行开始
+        Err(e) => {
+            let line = e.location().map(|l| l.line() + 1).unwrap_or(1);
+            Err((format!("Invalid YAML: {}", e), line))
+        }
+    }
+}
+
+fn lint_file(
+    path: &Path,
+    content: &str,
+    schema: &[FrontmatterField],
+    slugs: &HashMap<String, Vec<PathBuf>>,
+) -> Vec<FrontmatterDiagnostic> {
+    let path_str = path.to_string_lossy().to_string();
+    let mut lint = FileLint {
+        path: &path_str,
+        lines: FrontmatterLines::parse(content),
+        diagnostics: Vec::new(),
+    };
+
+    // 重复的键会导致 YAML 解析失败，因此先按行检查
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut has_duplicates = false;
+    let keys = lint.lines.as_ref().map(|l| l.keys()).unwrap_or_default();
+    for (key, line) in keys {
+        if let Some(first) = seen.get(&key) {
+            has_duplicates = true;
+            lint.diagnostics.push(FrontmatterDiagnostic {
+                path: path_str.clone(),
+                field: Some(key.clone()),
+                line,
+                severity: Severity::Error,
+                message: format!("Duplicate key (first defined on line {})", first),
+            });
+        } else {
+            seen.insert(key, line);
+        }
+    }
+
+    let frontmatter = match lint.lines.as_ref().map(parse_frontmatter) {
+        Some(Ok(frontmatter)) => frontmatter,
+        Some(Err((message, line))) => {
+            // 重复键的错误已经单独报告
+            if !has_duplicates {
+                lint.diagnostics.push(FrontmatterDiagnostic {
+                    path: path_str.clone(),
+                    field: None,
+                    line,
+                    severity: Severity::Error,
+                    message,
+                });
+            }
+            return lint.diagnostics;
+        }
+        None => Frontmatter::new(),
+    };
+
+    for field in schema {
+        match frontmatter.get(&field.title).filter(|v| !v.is_null()) {
+            Some(value) => {
+                check_type(&mut lint, &field.title, field, value);
+                check_constraints(&mut lint, &field.title, field, value);
+            }
+            None if field.required => lint.diagnostics.push(FrontmatterDiagnostic {
+                path: path_str.clone(),
+                field: Some(field.title.clone()),
+                line: 1,
+                severity: Severity::Error,
+                message: format!("Missing required field \"{}\"", field.title),
+            }),
+            None => {}
+        }
+    }
+
+    if !schema.is_empty() {
+        let mut unknown: Vec<&String> = frontmatter
+            .keys()
+            .filter(|key| !schema.iter().any(|f| &f.title == *key))
+            .collect();
+        unknown.sort();
+        for key in unknown {
+            lint.push(
+                Some(key),
+                Severity::Info,
+                format!("\"{}\" is not defined in the schema", key),
+            );
+        }
+    }
+
+    if let Some((slug, _)) = frontmatter.get(SLUG_FIELD).and_then(scalar_value) {
+        let others: Vec<String> = slugs
+            .get(&slug)
+            .into_iter()
+            .flatten()
+            .filter(|other| other.as_path() != path)
+            .map(|other| other.to_string_lossy().to_string())
+            .collect();
+        if !others.is_empty() {
+            lint.push(
+                Some(SLUG_FIELD),
+                Severity::Error,
+                format!("Slug \"{}\" is also used by {}", slug, others.join(", ")),
+            );
+        }
+    }
+
+    lint.diagnostics
+}
+
+/// Tauri 命令：按当前工作区的字段定义检查 frontmatter，返回结构化的诊断信息。
+/// 指定 `path` 时只检查该文件（例如保存时），slug 仍与整个工作区比较。
+#[tauri::command]
+pub async fn lint_frontmatter(
+    app: AppHandle,
+    window: Window,
+    path: Option<String>,
+) -> Result<Vec<FrontmatterDiagnostic>, String> {
+    let workspace = require_workspace(&window)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let config = load_workspace_config(&app, &workspace)?;
+
+        let mut slugs: HashMap<String, Vec<PathBuf>> = HashMap::new();
+        for (file, frontmatter) in markdown_frontmatter(&app, &workspace)? {
+            let slug = frontmatter
+                .as_deref()
+                .and_then(|fm| fm.get(SLUG_FIELD))
+                .and_then(scalar_value);
+            if let Some((slug, _)) = slug {
+                slugs.entry(slug).or_default().push(file);
+            }
+        }
+
+        let files = match path {
+            Some(path) => vec![PathBuf::from(path)],
+            None => markdown_files(&app, &workspace)?,
+        };
+        let mut diagnostics = Vec::new();
+        for file in files {
+            // 无法读取（或不是 UTF-8）的文件记为错误，不中断其余文件的检查
+            let content = match fs::read_to_string(&file) {
+                Ok(content) => content,
+                Err(e) => {
+                    diagnostics.push(FrontmatterDiagnostic {
+                        path: file.to_string_lossy().to_string(),
+                        field: None,
+                        line: 1,
+                        severity: Severity::Error,
+                        message: format!("Failed to read file: {}", e),
+                    });
+                    continue;
+                }
+            };
+            let schema = config.schema_for(&workspace, &file);
+            diagnostics.extend(lint_file(&file, &content, schema, &slugs));
+        }
+        diagnostics.sort_by(|a, b| {
+            a.path
+                .cmp(&b.path)
+                .then_with(|| a.line.cmp(&b.line))
+                .then_with(|| a.severity.cmp(&b.severity))
+        });
+        Ok(diagnostics)
+    })
+    .await
+    .map_err(|e| format!("Background thread failed: {}", e))?
+}
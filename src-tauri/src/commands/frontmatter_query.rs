@@ -0,0 +1,637 @@
+use super::frontmatter_dates::parse_date;
+use super::workspace_config::require_workspace;
+use super::workspace_index::{markdown_frontmatter, Frontmatter};
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use std::cmp::Ordering;
+use tauri::{AppHandle, Window};
+
+/// 查询表达式
+///
+/// 语法（关键字不区分大小写）：
+///
+/// ```text
+/// expr       := and_expr (("or" | "||") and_expr)*
+/// and_expr   := not_expr (("and" | "&&") not_expr)*
+/// not_expr   := ("not" | "!") not_expr | "(" expr ")" | condition
+/// condition  := "exists" field
+///             | field ("=" | "==" | "!=" | ">" | ">=" | "<" | "<=") value
+///             | field "contains" value
+///             | field "in" "[" value ("," value)* "]"
+/// field      := 字段名，可用 `.` 访问嵌套字段，例如 `author.name`
+/// value      := "带引号的字符串" | 'string' | 数字 | true | false | 不带引号的单词（如 2026-01-01）
+/// ```
+///
+/// 只有以数字（或正负号加数字）开头的单词才按数字解析，`inf`、`nan` 等仍是字符串；
+/// 两侧都能解析为日期时按日期比较，例如 `date > 2024-1-5`。
+///
+/// 示例：`draft = true and tags contains rust and date > 2026-01-01`
+#[derive(Debug, Clone)]
+pub enum Query {
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+    Exists(String),
+    Compare(String, CompareOp, Literal),
+    Contains(String, Literal),
+    In(String, Vec<Literal>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+#[derive(Debug, Clone)]
+pub enum Literal {
+    Str(String),
+    Num(f64),
+    Bool(bool),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = input.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '[' => {
+                tokens.push(Token::LBracket);
+                i += 1;
+            }
+            ']' => {
+                tokens.push(Token::RBracket);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            '"' | '\'' => {
+                let quote = c;
+                let mut value = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err("Unterminated string in query".to_string()),
+                        Some('\\') if chars.get(i + 1).is_some() => {
+                            value.push(chars[i + 1]);
+                            i += 2;
+                        }
+                        Some(&ch) if ch == quote => {
+                            i += 1;
+                            break;
+                        }
+                        Some(&ch) => {
+                            value.push(ch);
+                            i += 1;
+                        }
+                    }
+                }
+                tokens.push(Token::Quoted(value));
+            }
+            '=' | '!' | '<' | '>' | '&' | '|' => {
+                let next = chars.get(i + 1).copied();
+                let (op, len) = match (c, next) {
+                    ('=', Some('=')) => ("=", 2),
+                    ('=', _) => ("=", 1),
+                    ('!', Some('=')) => ("!=", 2),
+                    ('!', _) => ("!", 1),
+                    ('<', Some('=')) => ("<=", 2),
+                    ('<', _) => ("<", 1),
+                    ('>', Some('=')) => (">=", 2),
+                    ('>', _) => (">", 1),
+                    ('&', Some('&')) => ("&&", 2),
+                    ('|', Some('|')) => ("||", 2),
+                    _ => return Err(format!("Unexpected character '{}' in query", c)),
+                };
+                tokens.push(Token::Op(op));
+                i += len;
+            }
+            _ => {
+                let start = i;
+                while i < chars.len()
+                    && !chars[i].is_whitespace()
+                    && !"()[],=!<>&|\"'".contains(chars[i])
+                {
+                    i += 1;
+                }
+                tokens.push(Token::Word(chars[start..i].iter().collect()));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    fn parse_or(&mut self) -> Result<Query, String> {
+        let mut left = self.parse_and()?;
+        while self.peek_keyword("or") || self.peek() == Some(&Token::Op("||")) {
+            self.next();
+            let right = self.parse_and()?;
+            left = Query::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Query, String> {
+        let mut left = self.parse_not()?;
+        while self.peek_keyword("and") || self.peek() == Some(&Token::Op("&&")) {
+            self.next();
+            let right = self.parse_not()?;
+            left = Query::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<Query, String> {
+        if self.peek_keyword("not") || self.peek() == Some(&Token::Op("!")) {
+            self.next();
+            return Ok(Query::Not(Box::new(self.parse_not()?)));
+        }
+        if self.peek() == Some(&Token::LParen) {
+            self.next();
+            let inner = self.parse_or()?;
+            return match self.next() {
+                Some(Token::RParen) => Ok(inner),
+                _ => Err("Expected ')' in query".to_string()),
+            };
+        }
+        self.parse_condition()
+    }
+
+    fn parse_field(&mut self) -> Result<String, String> {
+        match self.next() {
+            Some(Token::Word(w)) => Ok(w),
+            Some(Token::Quoted(q)) => Ok(q),
+            other => Err(format!("Expected field name, found {:?}", other)),
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Literal, String> {
+        match self.next() {
+            Some(Token::Quoted(q)) => Ok(Literal::Str(q)),
+            Some(Token::Word(w)) => Ok(if w.eq_ignore_ascii_case("true") {
+                Literal::Bool(true)
+            } else if w.eq_ignore_ascii_case("false") {
+                Literal::Bool(false)
+            } else if let Some(n) = number_literal(&w) {
+                Literal::Num(n)
+            } else {
+                Literal::Str(w)
+            }),
+            other => Err(format!("Expected value, found {:?}", other)),
+        }
+    }
+
+    fn parse_condition(&mut self) -> Result<Query, String> {
+        if self.peek_keyword("exists") {
+            self.next();
+            return Ok(Query::Exists(self.parse_field()?));
+        }
+
+        let field = self.parse_field()?;
+        match self.next() {
+            Some(Token::Op(op)) => {
+                let op = match op {
+                    "=" => CompareOp::Eq,
+                    "!=" => CompareOp::Ne,
+                    ">" => CompareOp::Gt,
+                    ">=" => CompareOp::Ge,
+                    "<" => CompareOp::Lt,
+                    "<=" => CompareOp::Le,
+                    other => return Err(format!("Unexpected operator '{}' after field", other)),
+                };
+                Ok(Query::Compare(field, op, self.parse_value()?))
+            }
+            Some(Token::Word(w)) if w.eq_ignore_ascii_case("contains") => {
+                Ok(Query::Contains(field, self.parse_value()?))
+            }
+            Some(Token::Word(w)) if w.eq_ignore_ascii_case("exists") => Ok(Query::Exists(field)),
+            Some(Token::Word(w)) if w.eq_ignore_ascii_case("in") => {
+                if self.next() != Some(Token::LBracket) {
+                    return Err("Expected '[' after 'in'".to_string());
+                }
+                let mut values = Vec::new();
+                loop {
+                    if self.peek() == Some(&Token::RBracket) {
+                        self.next();
+                        break;
+                    }
+                    values.push(self.parse_value()?);
+                    match self.next() {
+                        Some(Token::Comma) => continue,
+                        Some(Token::RBracket) => break,
+                        _ => return Err("Expected ',' or ']' in list".to_string()),
+                    }
+                }
+                Ok(Query::In(field, values))
+            }
+            other => Err(format!(
+                "Expected operator after field '{}', found {:?}",
+                field, other
+            )),
+        }
+    }
+}
+
+/// 以数字（或正负号加数字）开头的单词解析为数字，避免 `inf`、`nan` 等被当作数字
+fn number_literal(word: &str) -> Option<f64> {
+    let digits = word.strip_prefix(['+', '-']).unwrap_or(word);
+    if !digits.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    word.parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+/// 解析查询表达式
+pub(crate) fn parse_query(input: &str) -> Result<Query, String> {
+    let tokens = tokenize(input)?;
+    if tokens.is_empty() {
+        return Err("Query is empty".to_string());
+    }
+    let mut parser = Parser { tokens, pos: 0 };
+    let query = parser.parse_or()?;
+    if parser.pos < parser.tokens.len() {
+        return Err(format!(
+            "Unexpected {:?} in query",
+            parser.tokens[parser.pos]
+        ));
+    }
+    Ok(query)
+}
+
+/// 按点号路径取出字段值，例如 `author.name`
+pub(crate) fn lookup<'a>(
+    frontmatter: &'a Frontmatter,
+    field: &str,
+) -> Option<&'a serde_yaml::Value> {
+    let mut parts = field.split('.');
+    let mut value = frontmatter.get(parts.next()?)?;
+    for part in parts {
+        value = match untag(value) {
+            serde_yaml::Value::Mapping(map) => map.get(part)?,
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+fn untag(value: &serde_yaml::Value) -> &serde_yaml::Value {
+    match value {
+        serde_yaml::Value::Tagged(tagged) => untag(&tagged.value),
+        other => other,
+    }
+}
+
+/// 日期或日期时间对应的时间点，不是日期时返回 None
+fn date_instant(s: &str) -> Option<NaiveDateTime> {
+    parse_date(s)?.instant()
+}
+
+/// 两个字符串都是日期时按时间点比较
+fn date_cmp(a: &str, b: &str) -> Option<Ordering> {
+    Some(date_instant(a)?.cmp(&date_instant(b)?))
+}
+
+fn scalar_eq(value: &serde_yaml::Value, literal: &Literal) -> bool {
+    match (untag(value), literal) {
+        (serde_yaml::Value::String(s), Literal::Str(l)) => {
+            s == l || date_cmp(s, l) == Some(Ordering::Equal)
+        }
+        (serde_yaml::Value::String(s), Literal::Num(n)) => s.trim().parse::<f64>() == Ok(*n),
+        (serde_yaml::Value::String(s), Literal::Bool(b)) => s.trim() == b.to_string(),
+        (serde_yaml::Value::Number(v), Literal::Num(n)) => v.as_f64() == Some(*n),
+        (serde_yaml::Value::Number(v), Literal::Str(s)) => v.to_string() == *s,
+        (serde_yaml::Value::Bool(v), Literal::Bool(b)) => v == b,
+        (serde_yaml::Value::Bool(v), Literal::Str(s)) => v.to_string() == *s,
+        (serde_yaml::Value::Null, Literal::Str(s)) => s == "null",
+        _ => false,
+    }
+}
+
+/// 数组元素是否等于字面量，字符串不区分大小写
+fn item_eq_ignore_case(value: &serde_yaml::Value, literal: &Literal) -> bool {
+    match (untag(value), literal) {
+        (serde_yaml::Value::String(s), Literal::Str(l)) => s.to_lowercase() == l.to_lowercase(),
+        _ => scalar_eq(value, literal),
+    }
+}
+
+/// 比较大小：数字按数值比较，日期按时间点比较，其余字符串按字典序比较
+fn scalar_cmp(value: &serde_yaml::Value, literal: &Literal) -> Option<Ordering> {
+    match (untag(value), literal) {
+        (serde_yaml::Value::Number(v), Literal::Num(n)) => v.as_f64()?.partial_cmp(n),
+        (serde_yaml::Value::String(s), Literal::Num(n)) => {
+            s.trim().parse::<f64>().ok()?.partial_cmp(n)
+        }
+        (serde_yaml::Value::String(s), Literal::Str(l)) => {
+            Some(date_cmp(s, l).unwrap_or_else(|| s.as_str().cmp(l.as_str())))
+        }
+        (serde_yaml::Value::Number(v), Literal::Str(l)) => Some(v.to_string().as_str().cmp(l)),
+        _ => None,
+    }
+}
+
+/// 对数组中的任一元素（或标量本身）判断条件
+fn any_item(value: &serde_yaml::Value, f: impl Fn(&serde_yaml::Value) -> bool) -> bool {
+    match untag(value) {
+        serde_yaml::Value::Sequence(seq) => seq.iter().any(f),
+        other => f(other),
+    }
+}
+
+impl Query {
+    /// 判断 frontmatter 是否满足查询
+    pub(crate) fn matches(&self, frontmatter: &Frontmatter) -> bool {
+        match self {
+            Query::And(a, b) => a.matches(frontmatter) && b.matches(frontmatter),
+            Query::Or(a, b) => a.matches(frontmatter) || b.matches(frontmatter),
+            Query::Not(inner) => !inner.matches(frontmatter),
+            Query::Exists(field) => lookup(frontmatter, field).is_some_and(|v| !untag(v).is_null()),
+            Query::Compare(field, op, literal) => {
+                let Some(value) = lookup(frontmatter, field) else {
+                    return *op == CompareOp::Ne;
+                };
+                match op {
+                    CompareOp::Eq => any_item(value, |v| scalar_eq(v, literal)),
+                    CompareOp::Ne => !any_item(value, |v| scalar_eq(v, literal)),
+                    _ => any_item(value, |v| match scalar_cmp(v, literal) {
+                        Some(ord) => match op {
+                            CompareOp::Gt => ord == Ordering::Greater,
+                            CompareOp::Ge => ord != Ordering::Less,
+                            CompareOp::Lt => ord == Ordering::Less,
+                            CompareOp::Le => ord != Ordering::Greater,
+                            CompareOp::Eq | CompareOp::Ne => unreachable!(),
+                        },
+                        None => false,
+                    }),
+                }
+            }
+            Query::Contains(field, literal) => {
+                let Some(value) = lookup(frontmatter, field) else {
+                    return false;
+                };
+                match (untag(value), literal) {
+                    // 数组中的字符串与字符串字段一样不区分大小写
+                    (serde_yaml::Value::Sequence(seq), _) => {
+                        seq.iter().any(|v| item_eq_ignore_case(v, literal))
+                    }
+                    // 字符串使用不区分大小写的子串匹配
+                    (serde_yaml::Value::String(s), Literal::Str(l)) => {
+                        s.to_lowercase().contains(&l.to_lowercase())
+                    }
+                    (other, _) => scalar_eq(other, literal),
+                }
+            }
+            Query::In(field, literals) => lookup(frontmatter, field).is_some_and(|value| {
+                literals
+                    .iter()
+                    .any(|l| any_item(value, |v| scalar_eq(v, l)))
+            }),
+        }
+    }
+}
+
+/// 查询结果
+#[derive(Serialize)]
+pub struct QueryMatch {
+    pub path: String,
+    /// 请求的字段值（未请求字段时为全部 frontmatter）
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Tauri 命令：用查询表达式筛选当前窗口工作区中的 Markdown 文件，
+/// 返回匹配的路径以及 `fields` 中指定的字段（支持 `a.b` 形式的嵌套字段）。
+#[tauri::command]
+pub async fn query_frontmatter(
+    app: AppHandle,
+    window: Window,
+    query: String,
+    fields: Option<Vec<String>>,
+) -> Result<Vec<QueryMatch>, String> {
+    let workspace = require_workspace(&window)?;
+    let query = parse_query(&query)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut matches = Vec::new();
+        for (path, frontmatter) in markdown_frontmatter(&app, &workspace)? {
+            let Some(frontmatter) = frontmatter else {
+                continue;
+            };
+            if !query.matches(&frontmatter) {
+                continue;
+            }
+
+            let mut selected = serde_json::Map::new();
+            match &fields {
+                Some(fields) => {
+                    for field in fields {
+                        let value = lookup(&frontmatter, field)
+                            .and_then(|v| serde_json::to_value(v).ok())
+                            .unwrap_or(serde_json::Value::Null);
+                        selected.insert(field.clone(), value);
+                    }
+                }
+                None => {
+                    for (key, value) in frontmatter.iter() {
+                        if let Ok(value) = serde_json::to_value(value) {
+                            selected.insert(key.clone(), value);
+                        }
+                    }
+                }
+            }
+
+            matches.push(QueryMatch {
+                path: path.to_string_lossy().to_string(),
+                fields: selected,
+            });
+        }
+        Ok(matches)
+    })
+    .await
+    .map_err(|e| format!("Background thread failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frontmatter(yaml: &str) -> Frontmatter {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn query_matches(query: &str, yaml: &str) -> bool {
+        parse_query(query).unwrap().matches(&frontmatter(yaml))
+    }
+
+    #[test]
+    fn tokenize_operators_and_words() {
+        assert_eq!(
+            tokenize("a>=1 && !(b != 'x y') || c in [1,2]").unwrap(),
+            vec![
+                Token::Word("a".to_string()),
+                Token::Op(">="),
+                Token::Word("1".to_string()),
+                Token::Op("&&"),
+                Token::Op("!"),
+                Token::LParen,
+                Token::Word("b".to_string()),
+                Token::Op("!="),
+                Token::Quoted("x y".to_string()),
+                Token::RParen,
+                Token::Op("||"),
+                Token::Word("c".to_string()),
+                Token::Word("in".to_string()),
+                Token::LBracket,
+                Token::Word("1".to_string()),
+                Token::Comma,
+                Token::Word("2".to_string()),
+                Token::RBracket,
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_quoted_strings() {
+        assert_eq!(
+            tokenize(r#""say \"hi\"" 'it''"#).unwrap_err(),
+            "Unterminated string in query"
+        );
+        assert_eq!(
+            tokenize(r#""say \"hi\"""#).unwrap(),
+            vec![Token::Quoted("say \"hi\"".to_string())]
+        );
+        assert!(tokenize("a & b").is_err());
+    }
+
+    #[test]
+    fn parse_precedence() {
+        let query = parse_query("a = 1 or b = 2 and not c = 3").unwrap();
+        let Query::Or(left, right) = query else {
+            panic!("expected or, got {:?}", query);
+        };
+        assert!(matches!(*left, Query::Compare(ref f, CompareOp::Eq, _) if f == "a"));
+        let Query::And(_, not) = *right else {
+            panic!("expected and, got {:?}", right);
+        };
+        assert!(matches!(*not, Query::Not(_)));
+    }
+
+    #[test]
+    fn parse_values() {
+        let value = |q: &str| match parse_query(q).unwrap() {
+            Query::Compare(_, _, literal) => literal,
+            other => panic!("expected comparison, got {:?}", other),
+        };
+        assert!(matches!(value("a = 1.5"), Literal::Num(n) if n == 1.5));
+        assert!(matches!(value("a = -2"), Literal::Num(n) if n == -2.0));
+        assert!(matches!(value("a = TRUE"), Literal::Bool(true)));
+        assert!(matches!(value("a = '1'"), Literal::Str(s) if s == "1"));
+        assert!(matches!(value("a = 2024-01-05"), Literal::Str(s) if s == "2024-01-05"));
+        for word in ["inf", "-inf", "nan", "NaN", "infinity"] {
+            let query = format!("a = {}", word);
+            assert!(
+                matches!(value(&query), Literal::Str(ref s) if s == word),
+                "{} should be a string",
+                word
+            );
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(parse_query("").is_err());
+        assert!(parse_query("(a = 1").is_err());
+        assert!(parse_query("a = 1 b").is_err());
+        assert!(parse_query("a in 1").is_err());
+        assert!(parse_query("a in [1 2]").is_err());
+        assert!(parse_query("a").is_err());
+    }
+
+    #[test]
+    fn parse_lists_and_exists() {
+        assert!(matches!(
+            parse_query("tags in []").unwrap(),
+            Query::In(_, ref values) if values.is_empty()
+        ));
+        assert!(
+            matches!(parse_query("exists author.name").unwrap(), Query::Exists(ref f) if f == "author.name")
+        );
+        assert!(
+            matches!(parse_query("title exists").unwrap(), Query::Exists(ref f) if f == "title")
+        );
+    }
+
+    #[test]
+    fn compares_dates_as_dates() {
+        assert!(query_matches("date > 2024-1-5", "date: 2024-01-10"));
+        assert!(!query_matches("date > 2024-1-5", "date: 2024-01-05"));
+        assert!(query_matches("date = 2024-1-5", "date: 2024-01-05"));
+        assert!(query_matches(
+            "date < 2024-01-05T12:00:00",
+            "date: 2024-01-05 08:00"
+        ));
+        assert!(query_matches("title > b", "title: c"));
+    }
+
+    #[test]
+    fn matches_fields() {
+        let yaml = "draft: false\ntags: [Rust, tauri]\nauthor:\n  name: Ann";
+        assert!(query_matches("draft = false and tags contains Rust", yaml));
+        assert!(query_matches("tags contains rust", yaml));
+        assert!(query_matches("tags contains TAURI", yaml));
+        assert!(!query_matches("tags contains rus", yaml));
+        assert!(query_matches("author.name = Ann", yaml));
+        assert!(query_matches("tags in [go, tauri]", yaml));
+        assert!(query_matches("missing != 1", yaml));
+        assert!(!query_matches("exists missing", yaml));
+    }
+}
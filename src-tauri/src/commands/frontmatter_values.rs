@@ -0,0 +1,249 @@
+use super::frontmatter_edit::FrontmatterLines;
+use super::undo::{write_changes, ChangeSummary, FileChange, SkippedFile};
+use super::workspace_config::require_workspace;
+use super::workspace_index::{markdown_frontmatter, scalar_value};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Window};
+
+/// 对某个字段中的值进行的批量修改
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ValueChange {
+    /// 将 `from` 重命名为 `to`
+    Rename { from: String, to: String },
+    /// 将 `values` 中的所有值合并为 `into`
+    Merge { values: Vec<String>, into: String },
+    /// 从字段中删除 `value`
+    Delete { value: String },
+}
+
+impl ValueChange {
+    fn validate(&self) -> Result<(), String> {
+        let target = match self {
+            ValueChange::Rename { from, to } if !from.is_empty() => to,
+            ValueChange::Merge { values, into } if !values.is_empty() => into,
+            ValueChange::Delete { value } if !value.is_empty() => return Ok(()),
+            _ => return Err("No values to change".to_string()),
+        };
+        if target.trim().is_empty() {
+            return Err("The new value must not be empty".to_string());
+        }
+        Ok(())
+    }
+
+    /// 会被修改的原值
+    fn sources(&self) -> Vec<&str> {
+        match self {
+            ValueChange::Rename { from, .. } => vec![from.as_str()],
+            ValueChange::Merge { values, .. } => values.iter().map(|v| v.as_str()).collect(),
+            ValueChange::Delete { value } => vec![value.as_str()],
+        }
+    }
+
+    /// 值修改后的结果，None 表示删除
+    fn apply(&self, value: &str) -> Option<String> {
+        match self {
+            ValueChange::Rename { from, to } if value == from => Some(to.clone()),
+            ValueChange::Merge { values, into } if values.iter().any(|v| v == value) => {
+                Some(into.clone())
+            }
+            ValueChange::Delete { value: deleted } if value == deleted => None,
+            _ => Some(value.to_string()),
+        }
+    }
+
+    fn description(&self, field: &str) -> String {
+        match self {
+            ValueChange::Rename { from, to } => {
+                format!("Rename {} \"{}\" to \"{}\"", field, from, to)
+            }
+            ValueChange::Merge { values, into } => {
+                format!("Merge {} {:?} into \"{}\"", field, values, into)
+            }
+            ValueChange::Delete { value } => format!("Delete {} \"{}\"", field, value),
+        }
+    }
+}
+
+/// 预览中受影响的单个文件
+#[derive(Serialize)]
+pub struct ValueChangePreview {
+    pub path: String,
+    /// 字段所在的行号（从 1 开始）
+    pub line: Option<usize>,
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+    /// 字段写法无法安全改写时的原因，此文件不会被修改
+    pub error: Option<String>,
+}
+
+fn contains_any(value: &serde_yaml::Value, sources: &[&str]) -> bool {
+    match value {
+        serde_yaml::Value::Sequence(seq) => seq.iter().any(|item| contains_any(item, sources)),
+        _ => scalar_value(value).is_some_and(|(text, _)| sources.contains(&text.as_str())),
+    }
+}
+
+/// 根据索引找出字段中含有待修改值的文件
+fn candidate_files(
+    app: &AppHandle,
+    workspace: &str,
+    field: &str,
+    change: &ValueChange,
+) -> Result<Vec<PathBuf>, String> {
+    let sources = change.sources();
+    Ok(markdown_frontmatter(app, workspace)?
+        .into_iter()
+        .filter(|(_, frontmatter)| {
+            frontmatter
+                .as_deref()
+                .and_then(|fm| fm.get(field))
+                .is_some_and(|value| contains_any(value, &sources))
+        })
+        .map(|(path, _)| path)
+        .collect())
+}
+
+/// 在内存中对单个文件应用修改，返回修改后的内容（未变化时为 None）
+fn rewrite(
+    content: &str,
+    field: &str,
+    change: &ValueChange,
+) -> Result<Option<(FrontmatterLines, FrontmatterLines)>, String> {
+    let Some(before) = FrontmatterLines::parse(content) else {
+        return Ok(None);
+    };
+    let mut after = before.clone();
+    if after.map_values(field, |value| change.apply(value))? {
+        Ok(Some((before, after)))
+    } else {
+        Ok(None)
+    }
+}
+
+fn check_field(field: &str) -> Result<(), String> {
+    if field.is_empty() || field.contains('.') {
+        return Err("Only top-level fields can be changed".to_string());
+    }
+    Ok(())
+}
+
+/// Tauri 命令：预览对字段 `field` 中的值进行重命名、合并或删除，不修改任何文件。
+#[tauri::command]
+pub async fn preview_value_change(
+    app: AppHandle,
+    window: Window,
+    field: String,
+    change: ValueChange,
+) -> Result<Vec<ValueChangePreview>, String> {
+    check_field(&field)?;
+    change.validate()?;
+    let workspace = require_workspace(&window)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut previews = Vec::new();
+        for path in candidate_files(&app, &workspace, &field, &change)? {
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let path_str = path.to_string_lossy().to_string();
+            match rewrite(&content, &field, &change) {
+                Ok(Some((before, after))) => previews.push(ValueChangePreview {
+                    path: path_str,
+                    line: before.key_line(&field),
+                    before: before.values(&field).unwrap_or_default(),
+                    after: after.values(&field).unwrap_or_default(),
+                    error: None,
+                }),
+                Ok(None) => {}
+                Err(e) => {
+                    let lines = FrontmatterLines::parse(&content);
+                    previews.push(ValueChangePreview {
+                        path: path_str,
+                        line: lines.as_ref().and_then(|l| l.key_line(&field)),
+                        before: Vec::new(),
+                        after: Vec::new(),
+                        error: Some(e),
+                    });
+                }
+            }
+        }
+        Ok(previews)
+    })
+    .await
+    .map_err(|e| format!("Background thread failed: {}", e))?
+}
+
+/// Tauri 命令：对字段 `field` 中的值进行重命名、合并或删除。
+/// 只改写涉及的行，所有被修改的文件记录为一条撤销记录，完成后刷新索引与建议。
+/// `paths` 不为空时只修改其中的文件（对应预览中选中的项）。
+#[tauri::command]
+pub async fn apply_value_change(
+    app: AppHandle,
+    window: Window,
+    field: String,
+    change: ValueChange,
+    paths: Option<Vec<String>>,
+) -> Result<ChangeSummary, String> {
+    check_field(&field)?;
+    change.validate()?;
+    let workspace = require_workspace(&window)?;
+    let description = change.description(&field);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let selected: Option<HashSet<&Path>> = paths
+            .as_ref()
+            .map(|paths| paths.iter().map(Path::new).collect());
+        let mut changes = Vec::new();
+        let mut skipped = Vec::new();
+
+        for path in candidate_files(&app, &workspace, &field, &change)? {
+            if selected
+                .as_ref()
+                .is_some_and(|s| !s.contains(path.as_path()))
+            {
+                continue;
+            }
+            let path_str = path.to_string_lossy().to_string();
+            let before = match fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(e) => {
+                    skipped.push(SkippedFile {
+                        path: path_str,
+                        reason: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+            let after = match rewrite(&before, &field, &change) {
+                Ok(Some((_, after))) => after.into_content(),
+                Ok(None) => continue,
+                Err(e) => {
+                    skipped.push(SkippedFile {
+                        path: path_str,
+                        reason: e,
+                    });
+                    continue;
+                }
+            };
+            changes.push(FileChange {
+                path,
+                before,
+                after,
+            });
+        }
+
+        Ok(write_changes(
+            &app,
+            &workspace,
+            description,
+            changes,
+            skipped,
+        ))
+    })
+    .await
+    .map_err(|e| format!("Background thread failed: {}", e))?
+}
@@ -1,9 +1,16 @@
+use super::frontmatter_dates::parse_date;
+use super::save_frontmatter::extract_frontmatter;
+use super::workspace_config::{load_workspace_config, save_workspace_config};
+use super::workspaces::{record_recent_workspace, window_workspace, WorkspaceState};
+use chrono::NaiveDateTime;
 use serde::Deserialize;
 use serde::Serialize;
+use std::cmp::Ordering;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
-use tauri::AppHandle;
+use std::time::SystemTime;
+use tauri::{AppHandle, Manager, Window};
 use tauri_plugin_dialog::DialogExt;
 use tauri_plugin_store::StoreExt;
 
@@ -17,6 +24,8 @@ pub struct TreeNode {
     pub children: Option<Vec<TreeNode>>,
     /// 文件的大小（字节），目录或未知为 None
     pub size: Option<u64>,
+    /// 虚拟视图中收纳没有对应字段的文章的分组
+    pub ungrouped: bool,
 }
 
 /// 限制以避免读取过深或过多节点的大文件夹。
@@ -24,9 +33,232 @@ pub struct TreeNode {
 const MAX_DEPTH: usize = 6;
 const MAX_NODES: usize = 5000;
 
+/// 文件树的排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortMode {
+    /// 按原始文件名排序（默认，与旧行为一致）
+    #[default]
+    Name,
+    /// 自然排序：数字按数值比较，`2-post` 排在 `10-post` 之前
+    Natural,
+    /// 按修改时间排序
+    Modified,
+    /// 按 frontmatter 中的 `date` 字段排序
+    FrontmatterDate,
+    /// 按 frontmatter 中的 `weight` 字段排序
+    FrontmatterWeight,
+}
+
+/// 每个工作区保存的文件树排序设置
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct TreeSortOrder {
+    #[serde(default)]
+    pub mode: SortMode,
+    /// 目录始终排在文件之前
+    #[serde(default)]
+    pub folders_first: bool,
+    /// 倒序（例如最新修改的排在最前）
+    #[serde(default)]
+    pub descending: bool,
+}
+
+/// 排序时为每个条目收集的信息
+struct SortEntry {
+    path: PathBuf,
+    name: String,
+    is_dir: bool,
+    modified: Option<SystemTime>,
+    /// 解析后的 `date`，无法解析的值视为缺失，排在最后
+    date: Option<NaiveDateTime>,
+    weight: Option<f64>,
+}
+
+impl SortEntry {
+    fn new(path: PathBuf, mode: SortMode) -> Self {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let metadata = fs::symlink_metadata(&path).ok();
+        let is_dir = metadata.as_ref().map(|m| m.is_dir()).unwrap_or(false);
+        let modified = match mode {
+            SortMode::Modified => metadata.as_ref().and_then(|m| m.modified().ok()),
+            _ => None,
+        };
+
+        // 只有按 frontmatter 排序时才读取 Markdown 文件内容
+        let frontmatter = match mode {
+            SortMode::FrontmatterDate | SortMode::FrontmatterWeight if !is_dir => {
+                if is_markdown(&path) {
+                    fs::read_to_string(&path)
+                        .ok()
+                        .and_then(|content| extract_frontmatter(&content))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+        let date = frontmatter
+            .as_ref()
+            .and_then(|fm| fm.get("date"))
+            .and_then(yaml_scalar_to_string)
+            .and_then(|date| parse_date(&date)?.instant());
+        let weight = frontmatter
+            .as_ref()
+            .and_then(|fm| fm.get("weight"))
+            .and_then(|v| match v {
+                serde_yaml::Value::Number(n) => n.as_f64(),
+                serde_yaml::Value::String(s) => s.trim().parse::<f64>().ok(),
+                _ => None,
+            });
+
+        SortEntry {
+            path,
+            name,
+            is_dir,
+            modified,
+            date,
+            weight,
+        }
+    }
+}
+
+fn is_markdown(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|s| s.to_str()),
+        Some("md") | Some("markdown")
+    )
+}
+
+fn yaml_scalar_to_string(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(s) => Some(s.clone()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        serde_yaml::Value::Tagged(tagged) => yaml_scalar_to_string(&tagged.value),
+        _ => None,
+    }
+}
+
+/// 自然排序比较：连续数字按数值比较，其余字符忽略大小写比较。
+pub(crate) fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => break,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(ca), Some(cb)) if ca.is_ascii_digit() && cb.is_ascii_digit() => {
+                let mut a_num = String::new();
+                while let Some(c) = a_chars.peek().copied().filter(|c| c.is_ascii_digit()) {
+                    a_num.push(c);
+                    a_chars.next();
+                }
+                let mut b_num = String::new();
+                while let Some(c) = b_chars.peek().copied().filter(|c| c.is_ascii_digit()) {
+                    b_num.push(c);
+                    b_chars.next();
+                }
+                // 去掉前导零后先比较位数，再按字典序比较，避免大数溢出
+                let a_trim = a_num.trim_start_matches('0');
+                let b_trim = b_num.trim_start_matches('0');
+                let ord = a_trim
+                    .len()
+                    .cmp(&b_trim.len())
+                    .then_with(|| a_trim.cmp(b_trim))
+                    .then_with(|| a_num.len().cmp(&b_num.len()));
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+            }
+            (Some(ca), Some(cb)) => {
+                let ord = ca.to_lowercase().cmp(cb.to_lowercase());
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+
+    // 仅大小写不同时退回到原始比较，保持顺序确定
+    a.cmp(b)
+}
+
+/// 比较两个可选值，缺失的值总是排在最后（不受倒序影响）。
+fn cmp_present_first<T>(
+    a: &Option<T>,
+    b: &Option<T>,
+    descending: bool,
+    cmp: impl Fn(&T, &T) -> Ordering,
+) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            let ord = cmp(a, b);
+            if descending {
+                ord.reverse()
+            } else {
+                ord
+            }
+        }
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+fn sort_entries(entries: &mut [SortEntry], order: &TreeSortOrder) {
+    entries.sort_by(|a, b| {
+        if order.folders_first && a.is_dir != b.is_dir {
+            return if a.is_dir {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            };
+        }
+
+        let by_name = || {
+            let ord = match order.mode {
+                SortMode::Name => a.path.file_name().cmp(&b.path.file_name()),
+                _ => natural_cmp(&a.name, &b.name),
+            };
+            if order.descending && matches!(order.mode, SortMode::Name | SortMode::Natural) {
+                ord.reverse()
+            } else {
+                ord
+            }
+        };
+
+        let ord = match order.mode {
+            SortMode::Name | SortMode::Natural => Ordering::Equal,
+            SortMode::Modified => {
+                cmp_present_first(&a.modified, &b.modified, order.descending, |x, y| x.cmp(y))
+            }
+            SortMode::FrontmatterDate => {
+                cmp_present_first(&a.date, &b.date, order.descending, |x, y| x.cmp(y))
+            }
+            SortMode::FrontmatterWeight => {
+                cmp_present_first(&a.weight, &b.weight, order.descending, |x, y| {
+                    x.total_cmp(y)
+                })
+            }
+        };
+        ord.then_with(by_name)
+    });
+}
+
 /// 递归为 `path` 构建 TreeNode。
 /// 使用 `node_count` 在递归中强制全局最大节点限制。
-fn build_tree(path: &Path, depth: usize, node_count: &mut usize) -> Result<TreeNode, io::Error> {
+fn build_tree(
+    path: &Path,
+    depth: usize,
+    node_count: &mut usize,
+    order: &TreeSortOrder,
+) -> Result<TreeNode, io::Error> {
     // 如果达到节点限制，提前退出
     if *node_count >= MAX_NODES {
         return Err(io::Error::new(io::ErrorKind::Other, "node limit reached"));
@@ -48,19 +280,19 @@ fn build_tree(path: &Path, depth: usize, node_count: &mut usize) -> Result<TreeN
             // 读取条目；如果读取目录出错，跳过 children 但返回节点
             match fs::read_dir(path) {
                 Ok(read_dir) => {
-                    // 收集条目并按文件名排序以保持确定性顺序
-                    let mut entries: Vec<PathBuf> = read_dir
-                        .filter_map(|res| res.ok().and_then(|e| Some(e.path())))
+                    // 收集条目并按配置的排序方式排序以保持确定性顺序
+                    let mut entries: Vec<SortEntry> = read_dir
+                        .filter_map(|res| res.ok().map(|e| SortEntry::new(e.path(), order.mode)))
                         .collect();
-                    entries.sort_by_key(|p| p.file_name().map(|n| n.to_os_string()));
+                    sort_entries(&mut entries, order);
 
-                    for entry_path in entries {
+                    for entry in entries {
                         if *node_count >= MAX_NODES {
                             break;
                         }
 
                         // 通过对每个条目使用 symlink_metadata 避免跟随符号链接目录
-                        match build_tree(&entry_path, depth + 1, node_count) {
+                        match build_tree(&entry.path, depth + 1, node_count, order) {
                             Ok(child_node) => {
                                 children.push(child_node);
                             }
@@ -84,6 +316,7 @@ fn build_tree(path: &Path, depth: usize, node_count: &mut usize) -> Result<TreeN
             is_dir: true,
             children: Some(children),
             size: None,
+            ungrouped: false,
         })
     } else {
         // 文件：如果可用，获取大小
@@ -95,6 +328,7 @@ fn build_tree(path: &Path, depth: usize, node_count: &mut usize) -> Result<TreeN
             is_dir: false,
             children: None,
             size: Some(size),
+            ungrouped: false,
         })
     }
 }
@@ -102,7 +336,7 @@ fn build_tree(path: &Path, depth: usize, node_count: &mut usize) -> Result<TreeN
 /// Tauri 命令：弹出文件夹选择器，选择文件夹并保存路径，然后构建文件树。
 /// 重/阻塞工作（对话框 + 文件系统遍历）在阻塞线程中执行，以避免阻塞主线程。
 #[tauri::command]
-pub async fn get_file_tree(app: AppHandle) -> Result<TreeNode, String> {
+pub async fn get_file_tree(app: AppHandle, window: Window) -> Result<TreeNode, String> {
     // 克隆句柄以在阻塞闭包中使用
     let app_handle = app.clone();
 
@@ -123,10 +357,17 @@ pub async fn get_file_tree(app: AppHandle) -> Result<TreeNode, String> {
             .store(".settings.dat")
             .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("无法访问存储: {}", e)))?;
         store.set("selectedPath", root_path.to_string_lossy().to_string());
+        app_handle
+            .state::<WorkspaceState>()
+            .set(window.label(), &root_path.to_string_lossy());
+        record_recent_workspace(&app_handle, &root_path.to_string_lossy())
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        let order = load_sort_order(&app_handle, &root_path.to_string_lossy())
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
 
         // 使用限制构建树以避免内存/时间爆炸
         let mut node_count: usize = 0;
-        match build_tree(&root_path, 0usize, &mut node_count) {
+        match build_tree(&root_path, 0usize, &mut node_count, &order) {
             Ok(tree) => Ok(tree),
             Err(e) => Err(e),
         }
@@ -142,14 +383,16 @@ pub async fn get_file_tree(app: AppHandle) -> Result<TreeNode, String> {
 
 /// Tauri 命令：从指定路径构建文件树，而不弹出选择器。
 #[tauri::command]
-pub async fn get_file_tree_from_path(path: String) -> Result<TreeNode, String> {
+pub async fn get_file_tree_from_path(app: AppHandle, path: String) -> Result<TreeNode, String> {
+    let order = load_sort_order(&app, &path)?;
+
     // 在阻塞线程中运行文件系统遍历，以避免阻塞主线程。
     let res = tauri::async_runtime::spawn_blocking(move || {
         let root_path = PathBuf::from(path);
 
         // 使用限制构建树以避免内存/时间爆炸
         let mut node_count: usize = 0;
-        match build_tree(&root_path, 0usize, &mut node_count) {
+        match build_tree(&root_path, 0usize, &mut node_count, &order) {
             Ok(tree) => Ok(tree),
             Err(e) => Err(e),
         }
@@ -163,9 +406,14 @@ pub async fn get_file_tree_from_path(path: String) -> Result<TreeNode, String> {
     }
 }
 
-/// Tauri 命令：获取存储的文件夹路径。
+/// Tauri 命令：获取当前窗口的文件夹路径（未单独设置时为存储的路径）。
 #[tauri::command]
-pub fn get_stored_path(app: AppHandle) -> Result<Option<String>, String> {
+pub fn get_stored_path(window: Window) -> Result<Option<String>, String> {
+    window_workspace(&window)
+}
+
+/// 读取存储中当前选择的工作区路径。
+pub(crate) fn stored_workspace(app: &AppHandle) -> Result<Option<String>, String> {
     let store = app
         .store(".settings.dat")
         .map_err(|e| format!("无法访问存储: {}", e))?;
@@ -184,6 +432,7 @@ pub struct SetWorkingDirectoryRequest {
 #[tauri::command]
 pub fn set_working_directory(
     app: AppHandle,
+    window: Window,
     request: SetWorkingDirectoryRequest,
 ) -> Result<(), String> {
     let path = PathBuf::from(&request.path);
@@ -201,7 +450,91 @@ pub fn set_working_directory(
     let store = app
         .store(".settings.dat")
         .map_err(|e| format!("无法访问存储: {}", e))?;
-    store.set("selectedPath", request.path);
+    store.set("selectedPath", request.path.clone());
+    app.state::<WorkspaceState>()
+        .set(window.label(), &request.path);
+    record_recent_workspace(&app, &request.path)?;
 
     Ok(())
 }
+
+/// 读取某个工作区保存的排序设置，未设置时返回默认值。
+fn load_sort_order(app: &AppHandle, workspace: &str) -> Result<TreeSortOrder, String> {
+    Ok(load_workspace_config(app, workspace)?.settings.tree_sort)
+}
+
+/// Tauri 命令：获取指定工作区的文件树排序设置。
+#[tauri::command]
+pub fn get_tree_sort_order(app: AppHandle, path: String) -> Result<TreeSortOrder, String> {
+    load_sort_order(&app, &path)
+}
+
+/// Tauri 命令：保存指定工作区的文件树排序设置。
+#[tauri::command]
+pub fn set_tree_sort_order(
+    app: AppHandle,
+    path: String,
+    order: TreeSortOrder,
+) -> Result<(), String> {
+    let mut config = load_workspace_config(&app, &path)?;
+    config.settings.tree_sort = order;
+    save_workspace_config(&app, &path, &config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, date: Option<&str>) -> SortEntry {
+        SortEntry {
+            path: PathBuf::from(name),
+            name: name.to_string(),
+            is_dir: false,
+            modified: None,
+            date: date.and_then(|d| parse_date(d)?.instant()),
+            weight: None,
+        }
+    }
+
+    fn names(entries: &[SortEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.name.as_str()).collect()
+    }
+
+    #[test]
+    fn natural_cmp_compares_numbers_by_value() {
+        assert_eq!(natural_cmp("2-post", "10-post"), Ordering::Less);
+        assert_eq!(natural_cmp("post-10", "post-9"), Ordering::Greater);
+        assert_eq!(natural_cmp("a", "B"), Ordering::Less);
+        assert_eq!(natural_cmp("007", "7"), Ordering::Greater);
+        assert_eq!(natural_cmp("post", "post-1"), Ordering::Less);
+        assert_eq!(
+            natural_cmp("99999999999999999999999", "100000000000000000000000"),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn frontmatter_date_sorts_parsed_dates_and_missing_last() {
+        let order = TreeSortOrder {
+            mode: SortMode::FrontmatterDate,
+            folders_first: false,
+            descending: false,
+        };
+        let mut entries = vec![
+            entry("c", Some("2024-1-10")),
+            entry("d", Some("not a date")),
+            entry("a", Some("2024-01-05 10:00")),
+            entry("b", Some("2024/01/09")),
+            entry("e", None),
+        ];
+        sort_entries(&mut entries, &order);
+        assert_eq!(names(&entries), ["a", "b", "c", "d", "e"]);
+
+        let order = TreeSortOrder {
+            descending: true,
+            ..order
+        };
+        sort_entries(&mut entries, &order);
+        assert_eq!(names(&entries), ["c", "b", "a", "d", "e"]);
+    }
+}
@@ -0,0 +1,561 @@
+use super::save_frontmatter::{frontmatter_fields, store_frontmatter_fields, FrontmatterField};
+use super::schema_files::{merge_schema, validate_schema, ImportMode};
+use serde_json::{json, Map, Value};
+use std::fs;
+use tauri::{AppHandle, Window};
+
+const SCHEMA_DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+/// `$ref` 展开的最大层数，防止循环引用
+const MAX_REF_DEPTH: usize = 16;
+
+/// 解析文档内的 `$ref`（`#/definitions/x`、`#/$defs/x` 等 JSON Pointer）
+fn resolve<'a>(root: &'a Value, mut schema: &'a Value) -> Result<&'a Value, String> {
+    for _ in 0..MAX_REF_DEPTH {
+        let Some(reference) = schema.get("$ref").and_then(|r| r.as_str()) else {
+            return Ok(schema);
+        };
+        let pointer = reference
+            .strip_prefix('#')
+            .ok_or_else(|| format!("Only local $ref is supported: {}", reference))?;
+        schema = root
+            .pointer(pointer)
+            .ok_or_else(|| format!("Cannot resolve $ref {}", reference))?;
+    }
+    Err("Too many nested $ref".to_string())
+}
+
+/// `anyOf` / `oneOf` 中除 null 以外的候选类型；没有组合时为自身
+fn variants<'a>(root: &'a Value, schema: &'a Value) -> Result<Vec<&'a Value>, String> {
+    let schema = resolve(root, schema)?;
+    let options = schema
+        .get("anyOf")
+        .or_else(|| schema.get("oneOf"))
+        .and_then(|o| o.as_array());
+    let Some(options) = options else {
+        return Ok(vec![schema]);
+    };
+    let mut result = Vec::new();
+    for option in options {
+        let option = resolve(root, option)?;
+        if schema_type(option) != Some("null") {
+            result.push(option);
+        }
+    }
+    Ok(result)
+}
+
+/// `type` 可能是字符串或数组（如 `["string", "null"]`），取第一个非 null 的类型
+fn schema_type(schema: &Value) -> Option<&str> {
+    match schema.get("type")? {
+        Value::String(t) => Some(t),
+        Value::Array(types) => types
+            .iter()
+            .filter_map(|t| t.as_str())
+            .find(|t| *t != "null"),
+        _ => None,
+    }
+}
+
+fn date_type(format: Option<&str>) -> Option<&'static str> {
+    match format? {
+        "date" => Some("date"),
+        "date-time" => Some("dateandtime"),
+        "time" => Some("time"),
+        _ => None,
+    }
+}
+
+fn enum_values(schema: &Value) -> Vec<String> {
+    let values = match (schema.get("enum"), schema.get("const")) {
+        (Some(Value::Array(values)), _) => values.iter().collect(),
+        (_, Some(value)) => vec![value],
+        _ => Vec::new(),
+    };
+    values
+        .into_iter()
+        .filter_map(|v| match v {
+            Value::String(s) => Some(s.clone()),
+            Value::Number(n) => Some(n.to_string()),
+            Value::Bool(b) => Some(b.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// 将 JSON Schema 中的一个属性转换为字段定义
+fn property_field(root: &Value, title: &str, schema: &Value) -> Result<FrontmatterField, String> {
+    let options = variants(root, schema)?;
+    let outer = resolve(root, schema)?;
+    let mut field = FrontmatterField {
+        title: title.to_string(),
+        ..Default::default()
+    };
+
+    // 日期可能写成多种候选格式（Astro 的 `z.coerce.date()`），取其中精度最高的
+    let dates: Vec<&str> = options
+        .iter()
+        .filter_map(|o| date_type(o.get("format").and_then(|f| f.as_str())))
+        .collect();
+    let primary = options.first().copied().unwrap_or(outer);
+
+    field.field_type = if dates.contains(&"dateandtime") {
+        "dateandtime".to_string()
+    } else if let Some(date) = dates.first() {
+        date.to_string()
+    } else {
+        match schema_type(primary) {
+            Some("integer") => {
+                field.integer = true;
+                "number".to_string()
+            }
+            Some("number") => "number".to_string(),
+            Some("boolean") => "boolean".to_string(),
+            Some("object") => {
+                field.fields = object_fields(root, primary)?;
+                "object".to_string()
+            }
+            Some("array") => {
+                let items = primary.get("items").unwrap_or(&Value::Null);
+                let item = variants(root, items)?.first().copied().unwrap_or(items);
+                field.allowed_values = enum_values(item);
+                match schema_type(item) {
+                    Some("integer") => {
+                        field.integer = true;
+                        "number[]".to_string()
+                    }
+                    Some("number") => "number[]".to_string(),
+                    Some("object") => {
+                        field.fields = object_fields(root, item)?;
+                        "object[]".to_string()
+                    }
+                    _ => "string[]".to_string(),
+                }
+            }
+            _ => "string".to_string(),
+        }
+    };
+
+    for source in [primary, outer] {
+        if field.allowed_values.is_empty() {
+            field.allowed_values = enum_values(source);
+        }
+        if field.description.is_none() {
+            field.description = source
+                .get("description")
+                .and_then(|d| d.as_str())
+                .map(str::to_string);
+        }
+        if field.label.is_none() {
+            field.label = source
+                .get("title")
+                .and_then(|t| t.as_str())
+                .map(str::to_string);
+        }
+        if field.default.is_none() {
+            field.default = source.get("default").cloned();
+        }
+        if field.pattern.is_none() && field.field_type.starts_with("string") {
+            field.pattern = source
+                .get("pattern")
+                .and_then(|p| p.as_str())
+                .map(str::to_string);
+        }
+        if field.field_type.starts_with("number") {
+            let integer = field.integer;
+            field.min = field
+                .min
+                .or_else(|| bound(source, "minimum", "exclusiveMinimum", integer, false));
+            field.max = field
+                .max
+                .or_else(|| bound(source, "maximum", "exclusiveMaximum", integer, true));
+        }
+    }
+    Ok(field)
+}
+
+/// 字段定义使用闭区间的 min / max。开区间边界（draft-06 起为数值，draft-04 为修饰
+/// `minimum` / `maximum` 的布尔值）对整数字段换算为最近的可取整数，其余类型无法表示，忽略
+fn bound(
+    source: &Value,
+    inclusive_key: &str,
+    exclusive_key: &str,
+    integer: bool,
+    upper: bool,
+) -> Option<f64> {
+    let inclusive = source.get(inclusive_key).and_then(|m| m.as_f64());
+    let (inclusive, exclusive) = match source.get(exclusive_key) {
+        Some(Value::Bool(true)) => (None, inclusive),
+        Some(Value::Bool(false)) | None => (inclusive, None),
+        Some(other) => (inclusive, other.as_f64()),
+    };
+    let exclusive = exclusive.filter(|_| integer).map(|e| {
+        if upper {
+            e.ceil() - 1.0
+        } else {
+            e.floor() + 1.0
+        }
+    });
+    match (inclusive, exclusive) {
+        (Some(a), Some(b)) => Some(if upper { a.min(b) } else { a.max(b) }),
+        (a, b) => a.or(b),
+    }
+}
+
+/// 对象类型 schema 中 `properties` 对应的字段定义
+fn object_fields(root: &Value, schema: &Value) -> Result<Vec<FrontmatterField>, String> {
+    let schema = resolve(root, schema)?;
+    let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) else {
+        return Ok(Vec::new());
+    };
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(|r| r.as_array())
+        .map(|r| r.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+
+    let mut fields = Vec::new();
+    for (title, property) in properties {
+        // Astro 导出的 schema 中带有供编辑器使用的 `$schema` 属性
+        if title == "$schema" {
+            continue;
+        }
+        let mut field = property_field(root, title, property)?;
+        field.key = fields.len();
+        field.required = required.contains(&title.as_str());
+        fields.push(field);
+    }
+    Ok(fields)
+}
+
+/// 将 JSON Schema 文档转换为字段定义。根对象可以通过 `$ref` 指向 `definitions`
+/// （Astro 在 `.astro/collections/*.schema.json` 中导出的格式）
+pub(crate) fn json_schema_to_fields(document: &Value) -> Result<Vec<FrontmatterField>, String> {
+    let root = resolve(document, document)?;
+    if root.get("properties").is_none() {
+        return Err("The JSON Schema does not describe an object with properties".to_string());
+    }
+    object_fields(document, root)
+}
+
+fn enum_json(field: &FrontmatterField, item_type: &str) -> Option<Value> {
+    if field.allowed_values.is_empty() {
+        return None;
+    }
+    let values = field
+        .allowed_values
+        .iter()
+        .map(|v| match item_type {
+            "number" | "integer" => v
+                .parse::<f64>()
+                .ok()
+                .and_then(serde_json::Number::from_f64)
+                .map(Value::Number)
+                .unwrap_or_else(|| Value::String(v.clone())),
+            _ => Value::String(v.clone()),
+        })
+        .collect();
+    Some(Value::Array(values))
+}
+
+/// 单个字段对应的 JSON Schema
+fn field_schema(field: &FrontmatterField) -> Value {
+    let number_type = if field.integer { "integer" } else { "number" };
+    let mut schema = Map::new();
+    let mut item = Map::new();
+    match field.field_type.as_str() {
+        "number" => {
+            schema.insert("type".into(), json!(number_type));
+        }
+        "boolean" => {
+            schema.insert("type".into(), json!("boolean"));
+        }
+        "date" | "time" | "dateandtime" => {
+            schema.insert("type".into(), json!("string"));
+            // 只在输出符合 RFC 3339 时声明 format：自定义格式不一定符合，
+            // 时间的默认格式 `%H:%M:%S` 不带时区，也不符合 `time`（full-time）
+            let default_format = field
+                .date_format
+                .as_deref()
+                .is_none_or(|f| f.trim().is_empty());
+            let format = match field.field_type.as_str() {
+                "date" => Some("date"),
+                "dateandtime" => Some("date-time"),
+                _ => None,
+            };
+            if let Some(format) = format.filter(|_| default_format) {
+                schema.insert("format".into(), json!(format));
+            }
+        }
+        "object" => return object_schema(&field.fields, Some(field)),
+        "object[]" => {
+            schema.insert("type".into(), json!("array"));
+            schema.insert("items".into(), object_schema(&field.fields, None));
+        }
+        "string[]" | "number[]" => {
+            let item_type = if field.field_type == "string[]" {
+                "string"
+            } else {
+                number_type
+            };
+            item.insert("type".into(), json!(item_type));
+            schema.insert("type".into(), json!("array"));
+        }
+        // save_as_array 的字符串字段保存为单元素数组
+        _ if field.save_as_array => {
+            item.insert("type".into(), json!("string"));
+            schema.insert("type".into(), json!("array"));
+            schema.insert("maxItems".into(), json!(1));
+        }
+        _ => {
+            schema.insert("type".into(), json!("string"));
+        }
+    }
+
+    // 数组字段的取值限制放在 items 上
+    let target = if schema.get("type") == Some(&json!("array")) && !item.is_empty() {
+        &mut item
+    } else {
+        &mut schema
+    };
+    let item_type = target
+        .get("type")
+        .and_then(|t| t.as_str())
+        .unwrap_or("string")
+        .to_string();
+    if let Some(values) = enum_json(field, &item_type) {
+        target.insert("enum".into(), values);
+    }
+    if let Some(pattern) = field.pattern.as_deref().filter(|p| !p.is_empty()) {
+        target.insert("pattern".into(), json!(pattern));
+    }
+    if let Some(min) = field.min {
+        target.insert("minimum".into(), json!(min));
+    }
+    if let Some(max) = field.max {
+        target.insert("maximum".into(), json!(max));
+    }
+    if !item.is_empty() {
+        schema.insert("items".into(), Value::Object(item));
+    }
+
+    describe(&mut schema, field);
+    Value::Object(schema)
+}
+
+fn describe(schema: &mut Map<String, Value>, field: &FrontmatterField) {
+    if let Some(label) = &field.label {
+        schema.insert("title".into(), json!(label));
+    }
+    if let Some(description) = &field.description {
+        schema.insert("description".into(), json!(description));
+    }
+    if let Some(default) = &field.default {
+        schema.insert("default".into(), default.clone());
+    }
+}
+
+fn object_schema(fields: &[FrontmatterField], field: Option<&FrontmatterField>) -> Value {
+    let properties: Map<String, Value> = fields
+        .iter()
+        .map(|f| (f.title.clone(), field_schema(f)))
+        .collect();
+    let required: Vec<&str> = fields
+        .iter()
+        .filter(|f| f.required)
+        .map(|f| f.title.as_str())
+        .collect();
+    let mut schema = Map::new();
+    schema.insert("type".into(), json!("object"));
+    schema.insert("properties".into(), Value::Object(properties));
+    if !required.is_empty() {
+        schema.insert("required".into(), json!(required));
+    }
+    if let Some(field) = field {
+        describe(&mut schema, field);
+    }
+    Value::Object(schema)
+}
+
+/// 将字段定义转换为 JSON Schema 文档
+pub(crate) fn fields_to_json_schema(fields: &[FrontmatterField]) -> Value {
+    let mut schema = object_schema(fields, None);
+    if let Value::Object(map) = &mut schema {
+        map.insert("$schema".into(), json!(SCHEMA_DRAFT));
+    }
+    schema
+}
+
+/// Tauri 命令：从 JSON Schema 文件（包括 Astro 导出的 content collection schema）导入字段定义，
+/// 返回导入后的字段定义。
+#[tauri::command]
+pub fn import_json_schema(
+    app: AppHandle,
+    window: Window,
+    path: String,
+    mode: Option<ImportMode>,
+) -> Result<Vec<FrontmatterField>, String> {
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let document: Value =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid JSON Schema: {}", e))?;
+    let imported = json_schema_to_fields(&document)?;
+    validate_schema(&imported)?;
+
+    let fields = merge_schema(
+        frontmatter_fields(&app, &window)?,
+        imported,
+        mode.unwrap_or_default(),
+    );
+    store_frontmatter_fields(&app, &window, fields.clone())?;
+    Ok(fields)
+}
+
+/// Tauri 命令：将当前字段定义导出为 JSON Schema 文件，可用于 CI 中校验 frontmatter。
+#[tauri::command]
+pub fn export_json_schema(app: AppHandle, window: Window, path: String) -> Result<(), String> {
+    let schema = fields_to_json_schema(&frontmatter_fields(&app, &window)?);
+    let content = serde_json::to_string_pretty(&schema)
+        .map_err(|e| format!("JSON serialization error: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(title: &str, field_type: &str) -> FrontmatterField {
+        FrontmatterField {
+            title: title.to_string(),
+            field_type: field_type.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn bound_turns_exclusive_integer_bounds_inclusive() {
+        let schema = json!({ "exclusiveMinimum": 0, "exclusiveMaximum": 10.5 });
+        assert_eq!(
+            bound(&schema, "minimum", "exclusiveMinimum", true, false),
+            Some(1.0)
+        );
+        assert_eq!(
+            bound(&schema, "maximum", "exclusiveMaximum", true, true),
+            Some(10.0)
+        );
+        // 非整数字段无法表示开区间
+        assert_eq!(
+            bound(&schema, "minimum", "exclusiveMinimum", false, false),
+            None
+        );
+
+        // draft-04 的布尔写法
+        let draft4 = json!({ "minimum": 5, "exclusiveMinimum": true, "maximum": 9 });
+        assert_eq!(
+            bound(&draft4, "minimum", "exclusiveMinimum", true, false),
+            Some(6.0)
+        );
+        assert_eq!(
+            bound(&draft4, "maximum", "exclusiveMaximum", true, true),
+            Some(9.0)
+        );
+
+        // 同时给出时取更严格的一个
+        let both = json!({ "minimum": 3, "exclusiveMinimum": 0 });
+        assert_eq!(
+            bound(&both, "minimum", "exclusiveMinimum", true, false),
+            Some(3.0)
+        );
+    }
+
+    #[test]
+    fn imports_astro_style_schema() {
+        let document = json!({
+            "$ref": "#/definitions/blog",
+            "definitions": {
+                "blog": {
+                    "type": "object",
+                    "required": ["title"],
+                    "properties": {
+                        "$schema": { "type": "string" },
+                        "title": { "type": "string", "description": "Post title" },
+                        "pubDate": {
+                            "anyOf": [
+                                { "type": "string", "format": "date-time" },
+                                { "type": "string", "format": "date" },
+                                { "type": "integer", "format": "unix-time" }
+                            ]
+                        },
+                        "rating": { "type": "integer", "minimum": 1, "exclusiveMaximum": 6 },
+                        "tags": { "type": "array", "items": { "enum": ["a", "b"] } },
+                        "draft": { "type": ["boolean", "null"], "default": false }
+                    }
+                }
+            }
+        });
+        let fields = json_schema_to_fields(&document).unwrap();
+        let by_title = |title: &str| fields.iter().find(|f| f.title == title).unwrap();
+
+        assert_eq!(fields.len(), 5);
+        assert!(by_title("title").required);
+        assert_eq!(by_title("title").description.as_deref(), Some("Post title"));
+        assert_eq!(by_title("pubDate").field_type, "dateandtime");
+        let rating = by_title("rating");
+        assert!(rating.integer);
+        assert_eq!((rating.min, rating.max), (Some(1.0), Some(5.0)));
+        assert_eq!(by_title("tags").field_type, "string[]");
+        assert_eq!(by_title("tags").allowed_values, vec!["a", "b"]);
+        assert_eq!(by_title("draft").field_type, "boolean");
+        assert_eq!(by_title("draft").default, Some(json!(false)));
+    }
+
+    #[test]
+    fn field_schema_declares_formats_only_for_rfc_3339_output() {
+        assert_eq!(
+            field_schema(&field("date", "date")),
+            json!({ "type": "string", "format": "date" })
+        );
+        assert_eq!(
+            field_schema(&field("updated", "dateandtime")),
+            json!({ "type": "string", "format": "date-time" })
+        );
+        assert_eq!(
+            field_schema(&field("at", "time")),
+            json!({ "type": "string" })
+        );
+        let custom = FrontmatterField {
+            date_format: Some("%Y/%m/%d".to_string()),
+            ..field("date", "date")
+        };
+        assert_eq!(field_schema(&custom), json!({ "type": "string" }));
+    }
+
+    #[test]
+    fn field_schema_puts_array_limits_on_items() {
+        let scores = FrontmatterField {
+            integer: true,
+            min: Some(0.0),
+            max: Some(10.0),
+            ..field("scores", "number[]")
+        };
+        assert_eq!(
+            field_schema(&scores),
+            json!({
+                "type": "array",
+                "items": { "type": "integer", "minimum": 0.0, "maximum": 10.0 }
+            })
+        );
+        let category = FrontmatterField {
+            save_as_array: true,
+            allowed_values: vec!["news".to_string()],
+            ..field("category", "string")
+        };
+        assert_eq!(
+            field_schema(&category),
+            json!({
+                "type": "array",
+                "maxItems": 1,
+                "items": { "type": "string", "enum": ["news"] }
+            })
+        );
+    }
+}
@@ -1,8 +1,28 @@
+pub mod auto_fields;
+pub mod bulk_edit;
+pub mod duplicate_values;
 pub mod file_operations;
+pub mod frontmatter_dates;
+pub mod frontmatter_edit;
+pub mod frontmatter_lint;
+pub mod frontmatter_query;
+pub mod frontmatter_values;
 pub mod get_file_content;
 pub mod get_file_tree;
+pub mod json_schema;
 pub mod lang;
 pub mod open_terminal;
+pub mod replace;
 pub mod save_frontmatter;
 pub mod save_markdown;
+pub mod schema_files;
+pub mod schema_inference;
+pub mod schema_presets;
+pub mod search;
 pub mod theme;
+pub mod undo;
+pub mod value_suggestions;
+pub mod virtual_tree;
+pub mod workspace_config;
+pub mod workspace_index;
+pub mod workspaces;
@@ -0,0 +1,346 @@
+use super::search::{
+    build_search_regex, frontmatter_line_count, parse_request_query, passes_filter, resolve_folder,
+    SearchMode, SearchRequest, SearchTarget,
+};
+use super::undo::{write_changes, FileChange, SkippedFile};
+use super::workspace_config::require_workspace;
+use super::workspace_index::markdown_files;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Window};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReplaceRequest {
+    #[serde(flatten)]
+    pub search: SearchRequest,
+    /// 替换文本；正则模式下支持 `$1`、`${name}` 等捕获组引用
+    pub replacement: String,
+}
+
+/// 预览中的单个替换
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplacePreview {
+    pub path: String,
+    /// 行号（从 1 开始）
+    pub line: usize,
+    /// 列号（从 1 开始，按字符计）
+    pub column: usize,
+    /// 被替换的原文
+    pub matched: String,
+    /// 替换后的文本
+    pub replacement: String,
+    pub original_line: String,
+    /// 只应用这一处替换后的整行内容
+    pub replaced_line: String,
+    pub in_frontmatter: bool,
+}
+
+/// 选中要应用的替换，对应预览中的一项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplaceSelection {
+    pub path: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReplaceSummary {
+    pub files_changed: usize,
+    pub replacements: usize,
+    /// 预览后内容已变化、未能应用的替换
+    pub stale: Vec<ReplaceSelection>,
+    /// 读写失败的文件及原因，其中的替换计入 `stale`
+    pub skipped: Vec<SkippedFile>,
+    /// 撤销记录 id，可传给 `undo_change`
+    pub undo_id: Option<u64>,
+    /// 文件已写入（可撤销），但刷新索引失败时的原因
+    pub warning: Option<String>,
+}
+
+/// 单行中的一处匹配
+struct LineMatch {
+    line: usize,
+    column: usize,
+    start: usize,
+    end: usize,
+    matched: String,
+    replacement: String,
+}
+
+/// 计算某一行中一处匹配的替换文本（正则模式展开捕获组，其余模式按字面替换）
+fn expand_replacement(
+    regex: &Regex,
+    mode: SearchMode,
+    line: &str,
+    start: usize,
+    replacement: &str,
+) -> String {
+    match mode {
+        SearchMode::Regex => match regex.captures_at(line, start) {
+            Some(caps) => {
+                let mut out = String::new();
+                caps.expand(replacement, &mut out);
+                out
+            }
+            None => replacement.to_string(),
+        },
+        _ => replacement.to_string(),
+    }
+}
+
+/// 去掉行尾的换行符，返回 (内容, 换行符)
+fn split_line_ending(line: &str) -> (&str, &str) {
+    if let Some(stripped) = line.strip_suffix("\r\n") {
+        (stripped, "\r\n")
+    } else if let Some(stripped) = line.strip_suffix('\n') {
+        (stripped, "\n")
+    } else {
+        (line, "")
+    }
+}
+
+fn find_line_matches(
+    content: &str,
+    regex: &Regex,
+    request: &ReplaceRequest,
+) -> Vec<(usize, Vec<LineMatch>)> {
+    let fm_lines = frontmatter_line_count(content);
+    let mut result = Vec::new();
+
+    for (index, raw_line) in content.split_inclusive('\n').enumerate() {
+        let in_frontmatter = index < fm_lines;
+        match request.search.target {
+            SearchTarget::Body if in_frontmatter => continue,
+            SearchTarget::Frontmatter if !in_frontmatter => break,
+            _ => {}
+        }
+
+        let (line, _) = split_line_ending(raw_line);
+        let matches: Vec<LineMatch> = regex
+            .find_iter(line)
+            .filter(|m| !m.as_str().is_empty())
+            .map(|m| LineMatch {
+                line: index + 1,
+                column: line[..m.start()].chars().count() + 1,
+                start: m.start(),
+                end: m.end(),
+                matched: m.as_str().to_string(),
+                replacement: expand_replacement(
+                    regex,
+                    request.search.mode,
+                    line,
+                    m.start(),
+                    &request.replacement,
+                ),
+            })
+            .collect();
+        if !matches.is_empty() {
+            result.push((index, matches));
+        }
+    }
+    result
+}
+
+fn candidate_files(
+    app: &AppHandle,
+    workspace: &str,
+    request: &ReplaceRequest,
+) -> Result<Vec<PathBuf>, String> {
+    let folder = request
+        .search
+        .folder
+        .as_deref()
+        .map(|folder| resolve_folder(workspace, folder));
+    Ok(markdown_files(app, workspace)?
+        .into_iter()
+        .filter(|path| folder.as_ref().is_none_or(|f| path.starts_with(f)))
+        .collect())
+}
+
+/// Tauri 命令：预览工作区替换，返回每一处匹配及替换后的行（不修改任何文件）。
+#[tauri::command]
+pub async fn preview_replace_in_workspace(
+    app: AppHandle,
+    window: Window,
+    request: ReplaceRequest,
+) -> Result<Vec<ReplacePreview>, String> {
+    let workspace = require_workspace(&window)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let regex = build_search_regex(
+            &request.search.query,
+            request.search.mode,
+            request.search.case_sensitive,
+        )?;
+        let query = parse_request_query(&request.search)?;
+        let mut previews = Vec::new();
+
+        for path in candidate_files(&app, &workspace, &request)? {
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            if !passes_filter(&content, &request.search.frontmatter_filter, query.as_ref()) {
+                continue;
+            }
+            let fm_lines = frontmatter_line_count(&content);
+            let lines: Vec<&str> = content.split_inclusive('\n').collect();
+
+            for (index, matches) in find_line_matches(&content, &regex, &request) {
+                let (line, _) = split_line_ending(lines[index]);
+                for m in matches {
+                    let replaced_line =
+                        format!("{}{}{}", &line[..m.start], m.replacement, &line[m.end..]);
+                    previews.push(ReplacePreview {
+                        path: path.to_string_lossy().to_string(),
+                        line: m.line,
+                        column: m.column,
+                        matched: m.matched,
+                        replacement: m.replacement,
+                        original_line: line.to_string(),
+                        replaced_line,
+                        in_frontmatter: index < fm_lines,
+                    });
+                }
+            }
+        }
+        Ok(previews)
+    })
+    .await
+    .map_err(|e| format!("Background thread failed: {}", e))?
+}
+
+/// Tauri 命令：只应用选中的替换。每个文件先在内存中完成全部替换，再原子写入；
+/// 所有成功写入的文件记录为一条撤销记录，某个文件读写失败时跳过它并继续。
+#[tauri::command]
+pub async fn replace_in_workspace(
+    app: AppHandle,
+    window: Window,
+    request: ReplaceRequest,
+    selections: Vec<ReplaceSelection>,
+) -> Result<ReplaceSummary, String> {
+    let workspace = require_workspace(&window)?;
+    let regex = build_search_regex(
+        &request.search.query,
+        request.search.mode,
+        request.search.case_sensitive,
+    )?;
+
+    // 按文件分组选中的替换
+    let mut by_file: BTreeMap<String, HashSet<(usize, usize)>> = BTreeMap::new();
+    for selection in &selections {
+        by_file
+            .entry(selection.path.clone())
+            .or_default()
+            .insert((selection.line, selection.column));
+    }
+
+    let description = format!(
+        "Replace \"{}\" with \"{}\"",
+        request.search.query, request.replacement
+    );
+    let (summary, replacements, applied) = tauri::async_runtime::spawn_blocking(move || {
+        let query = parse_request_query(&request.search)?;
+        let allowed: HashSet<PathBuf> = candidate_files(&app, &workspace, &request)?
+            .into_iter()
+            .collect();
+        let mut changes: Vec<FileChange> = Vec::new();
+        let mut skipped = Vec::new();
+        // 每个文件中应用的替换，写入成功后才计入结果
+        let mut applied_by_file: BTreeMap<String, Vec<(usize, usize)>> = BTreeMap::new();
+
+        for (path, wanted) in by_file {
+            let path_buf = PathBuf::from(&path);
+            if !allowed.contains(&path_buf) {
+                continue;
+            }
+            let before = match fs::read_to_string(&path_buf) {
+                Ok(content) => content,
+                Err(e) => {
+                    skipped.push(SkippedFile {
+                        path,
+                        reason: format!("Failed to read: {}", e),
+                    });
+                    continue;
+                }
+            };
+            if !passes_filter(&before, &request.search.frontmatter_filter, query.as_ref()) {
+                continue;
+            }
+
+            let line_matches = find_line_matches(&before, &regex, &request);
+            let mut lines: Vec<String> = before
+                .split_inclusive('\n')
+                .map(|l| l.to_string())
+                .collect();
+            let mut file_applied = Vec::new();
+
+            for (index, matches) in line_matches {
+                let (line, ending) = split_line_ending(&lines[index]);
+                let mut new_line = String::new();
+                let mut last = 0;
+                let mut changed = false;
+                for m in matches {
+                    if !wanted.contains(&(m.line, m.column)) {
+                        continue;
+                    }
+                    new_line.push_str(&line[last..m.start]);
+                    new_line.push_str(&m.replacement);
+                    last = m.end;
+                    changed = true;
+                    file_applied.push((m.line, m.column));
+                }
+                if changed {
+                    new_line.push_str(&line[last..]);
+                    new_line.push_str(ending);
+                    lines[index] = new_line;
+                }
+            }
+
+            if file_applied.is_empty() {
+                continue;
+            }
+            changes.push(FileChange {
+                path: path_buf,
+                before,
+                after: lines.concat(),
+            });
+            applied_by_file.insert(path, file_applied);
+        }
+
+        let summary = write_changes(&app, &workspace, description, changes, skipped);
+        let failed: HashSet<&str> = summary.skipped.iter().map(|s| s.path.as_str()).collect();
+        let mut applied: HashSet<(String, usize, usize)> = HashSet::new();
+        let mut replacements = 0;
+        for (path, positions) in &applied_by_file {
+            if failed.contains(path.as_str()) {
+                continue;
+            }
+            replacements += positions.len();
+            applied.extend(
+                positions
+                    .iter()
+                    .map(|&(line, column)| (path.clone(), line, column)),
+            );
+        }
+        Ok::<_, String>((summary, replacements, applied))
+    })
+    .await
+    .map_err(|e| format!("Background thread failed: {}", e))??;
+
+    let stale = selections
+        .into_iter()
+        .filter(|s| !applied.contains(&(s.path.clone(), s.line, s.column)))
+        .collect();
+
+    Ok(ReplaceSummary {
+        files_changed: summary.files_changed,
+        replacements,
+        stale,
+        skipped: summary.skipped,
+        undo_id: summary.undo_id,
+        warning: summary.warning,
+    })
+}
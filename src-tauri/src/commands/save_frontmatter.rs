@@ -1,22 +1,94 @@
+use super::frontmatter_dates::normalize_date;
+use super::workspace_config::{load_workspace_config, save_workspace_config};
+use super::workspace_index::{refresh_index, store_suggestions, WorkspaceIndex};
+use super::workspaces::window_workspace;
 use serde::{Deserialize, Serialize};
 use serde_json;
 use std::collections::HashMap;
-use std::fs;
+use tauri::Manager;
 use tauri_plugin_store::StoreExt;
-use walkdir::WalkDir;
 
-#[derive(Serialize, Deserialize, Clone)]
+/// Values the backend fills in when a post is saved
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoValue {
+    /// Current time whenever the body changes (`lastmod`, `updated`)
+    LastModified,
+    /// Derived from `title` when empty
+    Slug,
+    WordCount,
+    /// Estimated minutes to read
+    ReadingTime,
+    /// Generated once when empty
+    Uuid,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct FrontmatterField {
+    #[serde(default)]
     pub key: usize,
     pub title: String,
     pub field_type: String,
+    #[serde(default)]
     pub save_as_array: bool,
+    #[serde(default)]
     pub quote_strings: bool,
+    /// Every post must set this field
+    #[serde(default)]
+    pub required: bool,
+    /// Values accepted for this field; empty means any value
+    #[serde(default)]
+    pub allowed_values: Vec<String>,
+    /// Number fields only accept whole numbers; otherwise decimals are kept as written
+    #[serde(default)]
+    pub integer: bool,
+    /// chrono format string for date, time and dateandtime fields
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_format: Option<String>,
+    /// Offset dates are converted to, e.g. `+08:00` or `Z`; local time when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Quote dates so YAML reads them as strings instead of timestamps
+    #[serde(default)]
+    pub date_as_string: bool,
+    /// Computed on save instead of edited by hand
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto: Option<AutoValue>,
+    /// Value used when a post does not set this field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
+    /// Inclusive bounds for number fields
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    /// Regex that string values (and string[] items) must match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Help text shown next to the field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Display name; the YAML key is still `title`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Nested fields of `object` and `object[]` fields
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FrontmatterField>,
+}
+
+impl FrontmatterField {
+    /// Name shown to the user: the label if set, otherwise the YAML key
+    pub fn display_name(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.title)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct FrontmatterSuggestion {
     pub value: String,
+    /// Value type: string, number, boolean, date, time or dateandtime
+    #[serde(default)]
+    pub value_type: String,
     pub count: usize,
 }
 
@@ -28,8 +100,25 @@ pub struct FrontmatterSuggestions {
 #[tauri::command]
 pub async fn save_frontmatter(
     app_handle: tauri::AppHandle,
+    window: tauri::Window,
     fields: Vec<FrontmatterField>,
 ) -> Result<(), String> {
+    store_frontmatter_fields(&app_handle, &window, fields)
+}
+
+/// Saves the schema of the window's workspace, or the global schema when no workspace is open
+pub(crate) fn store_frontmatter_fields(
+    app_handle: &tauri::AppHandle,
+    window: &tauri::Window,
+    fields: Vec<FrontmatterField>,
+) -> Result<(), String> {
+    // Fields are scoped to the window's workspace when one is open
+    if let Some(workspace) = window_workspace(window)? {
+        let mut config = load_workspace_config(app_handle, &workspace)?;
+        config.frontmatter_fields = fields;
+        return save_workspace_config(app_handle, &workspace, &config);
+    }
+
     let store = app_handle
         .store(".frontmatter.dat")
         .map_err(|e| format!("Failed to open store: {}", e))?;
@@ -45,7 +134,20 @@ pub async fn save_frontmatter(
 #[tauri::command]
 pub async fn load_frontmatter(
     app_handle: tauri::AppHandle,
+    window: tauri::Window,
 ) -> Result<Vec<FrontmatterField>, String> {
+    frontmatter_fields(&app_handle, &window)
+}
+
+/// Schema of the window's workspace, or the global schema when no workspace is open
+pub(crate) fn frontmatter_fields(
+    app_handle: &tauri::AppHandle,
+    window: &tauri::Window,
+) -> Result<Vec<FrontmatterField>, String> {
+    if let Some(workspace) = window_workspace(window)? {
+        return Ok(load_workspace_config(app_handle, &workspace)?.frontmatter_fields);
+    }
+
     let store = app_handle
         .store(".frontmatter.dat")
         .map_err(|e| format!("Failed to open store: {}", e))?;
@@ -57,30 +159,58 @@ pub async fn load_frontmatter(
     Ok(fields)
 }
 
+/// Schema for the file at `path`: the most specific folder schema of the window's
+/// workspace, falling back to the workspace or global schema
+pub(crate) fn frontmatter_fields_for_path(
+    app_handle: &tauri::AppHandle,
+    window: &tauri::Window,
+    path: &str,
+) -> Result<Vec<FrontmatterField>, String> {
+    if let Some(workspace) = window_workspace(window)? {
+        let config = load_workspace_config(app_handle, &workspace)?;
+        return Ok(config
+            .schema_for(&workspace, std::path::Path::new(path))
+            .to_vec());
+    }
+    frontmatter_fields(app_handle, window)
+}
+
+#[tauri::command]
+pub async fn load_frontmatter_for_path(
+    app_handle: tauri::AppHandle,
+    window: tauri::Window,
+    path: String,
+) -> Result<Vec<FrontmatterField>, String> {
+    frontmatter_fields_for_path(&app_handle, &window, &path)
+}
+
+/// Store key of the suggestions collected for `workspace` (global when `None`).
+pub(crate) fn suggestions_key(workspace: Option<&str>) -> String {
+    match workspace {
+        Some(workspace) => format!("frontmatter_suggestions:{}", workspace),
+        None => "frontmatter_suggestions".to_string(),
+    }
+}
+
 #[tauri::command]
-pub async fn collect_frontmatter_suggestions(app_handle: tauri::AppHandle) -> Result<(), String> {
-    let settings_store = app_handle
-        .store(".settings.dat")
-        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+pub async fn collect_frontmatter_suggestions(
+    app_handle: tauri::AppHandle,
+    window: tauri::Window,
+) -> Result<(), String> {
     let frontmatter_store = app_handle
         .store(".frontmatter.dat")
         .map_err(|e| format!("Failed to open frontmatter store: {}", e))?;
 
-    // Load stored path from settings store
-    let stored_path: Option<String> = serde_json::from_value(
-        settings_store
-            .get("selectedPath")
-            .unwrap_or(serde_json::Value::Null),
-    )
-    .unwrap_or(None);
+    // Load the workspace opened in the calling window
+    let stored_path: Option<String> = window_workspace(&window)?;
 
     let root_path = match stored_path {
         Some(path) => {
-            println!("收集建议：存储路径 = {}", path);
+            log::debug!("Collecting frontmatter suggestions for {}", path);
             path
         }
         None => {
-            println!("收集建议：未找到存储路径，返回空建议");
+            log::debug!("No workspace open, storing empty frontmatter suggestions");
             // No path selected, save empty suggestions
             let fm_suggestions = FrontmatterSuggestions {
                 field_suggestions: HashMap::new(),
@@ -96,92 +226,37 @@ pub async fn collect_frontmatter_suggestions(app_handle: tauri::AppHandle) -> Re
         }
     };
 
-    let mut suggestions: HashMap<String, HashMap<String, usize>> = HashMap::new();
-    let mut md_file_count = 0;
-    let mut frontmatter_count = 0;
-
-    // Walk through all .md files
-    for entry in WalkDir::new(&root_path).into_iter().filter_map(|e| e.ok()) {
-        if entry.path().extension().and_then(|s| s.to_str()) == Some("md") {
-            md_file_count += 1;
-            if let Ok(content) = fs::read_to_string(entry.path()) {
-                if let Some(frontmatter) = extract_frontmatter(&content) {
-                    frontmatter_count += 1;
-                    println!("找到 frontmatter 文件: {}", entry.path().display());
-                    for (key, value) in &frontmatter {
-                        println!("  字段: {} = {:?}", key, value);
-                        let field_sug = suggestions.entry(key.clone()).or_insert(HashMap::new());
-                        match value {
-                            serde_yaml::Value::String(s) => {
-                                *field_sug.entry(s.clone()).or_insert(0) += 1;
-                            }
-                            serde_yaml::Value::Sequence(seq) => {
-                                println!("  字段 {} 是数组，包含 {} 个元素", key, seq.len());
-                                for item in seq {
-                                    if let serde_yaml::Value::String(s) = item {
-                                        println!("    数组元素: {}", s);
-                                        *field_sug.entry(s.clone()).or_insert(0) += 1;
-                                    } else {
-                                        println!("    非字符串数组元素: {:?}", item);
-                                    }
-                                }
-                            }
-                            _ => {
-                                println!("  字段 {} 是其他类型: {:?}", key, value);
-                            }
-                        }
-                    }
-                } else {
-                    println!("文件 {} 没有有效的 frontmatter", entry.path().display());
-                }
-            } else {
-                println!("无法读取文件: {}", entry.path().display());
-            }
-        }
-    }
-
-    println!(
-        "扫描完成：找到 {} 个 md 文件，其中 {} 个有 frontmatter",
-        md_file_count, frontmatter_count
-    );
-    println!(
-        "收集到的建议字段: {:?}",
-        suggestions.keys().collect::<Vec<_>>()
-    );
-
-    // Convert to FrontmatterSuggestions
-    let mut field_suggestions: HashMap<String, Vec<FrontmatterSuggestion>> = HashMap::new();
-    for (field, values) in suggestions {
-        let mut sorted_suggestions: Vec<FrontmatterSuggestion> = values
-            .into_iter()
-            .map(|(value, count)| FrontmatterSuggestion { value, count })
-            .collect();
-        sorted_suggestions.sort_by(|a, b| b.count.cmp(&a.count));
-        field_suggestions.insert(field, sorted_suggestions);
-    }
-
-    let fm_suggestions = FrontmatterSuggestions { field_suggestions };
-    frontmatter_store.set(
-        "frontmatter_suggestions",
-        serde_json::to_value(fm_suggestions).unwrap(),
-    );
-    frontmatter_store
-        .save()
-        .map_err(|e| format!("Failed to save suggestions: {}", e))?;
-
-    Ok(())
+    // Only files whose mtime/size changed since the last scan are re-parsed
+    let workspace = root_path;
+    let app = app_handle.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        refresh_index(&app, &workspace)?;
+        store_suggestions(&app, &workspace)
+    })
+    .await
+    .map_err(|e| format!("Background thread failed: {}", e))?
 }
 
 #[tauri::command]
 pub async fn load_frontmatter_suggestions(
     app_handle: tauri::AppHandle,
+    window: tauri::Window,
 ) -> Result<FrontmatterSuggestions, String> {
+    let workspace = window_workspace(&window)?;
+    // Use the in-memory counts once the index is built; the stored suggestions may lag behind recent edits
+    if let Some(root) = workspace.as_deref() {
+        if let Ok(suggestions) = app_handle.state::<WorkspaceIndex>().suggestions(root) {
+            return Ok(suggestions);
+        }
+    }
+
     let store = app_handle
         .store(".frontmatter.dat")
         .map_err(|e| format!("Failed to open store: {}", e))?;
-
     let suggestions: FrontmatterSuggestions = serde_json::from_value(
-        store.get("frontmatter_suggestions").unwrap_or_default(),
+        store
+            .get(suggestions_key(workspace.as_deref()))
+            .unwrap_or_default(),
     )
     .unwrap_or(FrontmatterSuggestions {
         field_suggestions: HashMap::new(),
@@ -190,204 +265,415 @@ pub async fn load_frontmatter_suggestions(
     Ok(suggestions)
 }
 
-#[tauri::command]
-pub fn initialize_form_data(
-    schema: Vec<FrontmatterField>,
-    current_frontmatter: serde_json::Value,
-) -> Result<serde_json::Value, String> {
-    let mut form_data = serde_json::Map::new();
-    let current: HashMap<String, serde_json::Value> =
-        serde_json::from_value(current_frontmatter).unwrap_or_default();
-
-    for field in schema {
-        let val = current.get(&field.title);
-        match field.field_type.as_str() {
-            "string[]" => {
-                if let Some(v) = val {
-                    if v.is_array() {
-                        form_data.insert(field.title, v.clone());
-                    } else if v.is_string() {
-                        let arr: Vec<serde_json::Value> = v
-                            .as_str()
-                            .unwrap()
-                            .split(',')
-                            .map(|s| serde_json::Value::String(s.trim().to_string()))
-                            .filter(|s| !s.as_str().unwrap().is_empty())
-                            .collect();
-                        form_data.insert(field.title, serde_json::Value::Array(arr));
-                    } else {
-                        form_data.insert(field.title, serde_json::Value::Array(vec![]));
-                    }
-                } else {
-                    form_data.insert(field.title, serde_json::Value::Array(vec![]));
-                }
-            }
-            "date" | "time" | "dateandtime" => {
-                if let Some(v) = val {
-                    if v.is_string() {
-                        form_data.insert(field.title, v.clone());
-                    } else {
-                        form_data.insert(field.title, serde_json::Value::Null);
-                    }
-                } else {
-                    form_data.insert(field.title, serde_json::Value::Null);
-                }
+/// Splits a comma-separated string into trimmed, non-empty string values
+fn split_list(s: &str) -> Vec<serde_json::Value> {
+    s.split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| serde_json::Value::String(s.to_string()))
+        .collect()
+}
+
+fn strip_quotes(s: &str) -> String {
+    if s.len() >= 2 && s.starts_with('"') && s.ends_with('"') {
+        s[1..s.len() - 1].to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+fn to_bool(value: &serde_json::Value) -> Option<bool> {
+    match value {
+        serde_json::Value::Bool(b) => Some(*b),
+        serde_json::Value::Number(n) => n.as_f64().map(|n| n != 0.0),
+        serde_json::Value::String(s) => match s.trim().to_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Some(true),
+            "false" | "no" | "off" | "0" | "" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Parses a number, keeping decimals unless `integer` is set; errors finish "\"<field>\" ..."
+fn to_number(value: &serde_json::Value, integer: bool) -> Result<serde_json::Value, &'static str> {
+    let n = match value {
+        serde_json::Value::Number(n) if n.is_i64() || n.is_u64() => {
+            return if integer && n.is_u64() && !n.is_i64() {
+                Err("is out of range for an integer")
+            } else {
+                Ok(value.clone())
+            };
+        }
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::String(s) => {
+            let s = s.trim();
+            if let Ok(n) = s.parse::<i64>() {
+                return Ok(n.into());
             }
-            "string" => {
-                if let Some(v) = val {
-                    if v.is_array() && !v.as_array().unwrap().is_empty() {
-                        // If saved as array, take the first element
-                        let first = &v.as_array().unwrap()[0];
-                        if first.is_string() {
-                            let mut s = first.as_str().unwrap().to_string();
-                            if s.starts_with('"') && s.ends_with('"') {
-                                s = s[1..s.len() - 1].to_string();
-                            }
-                            form_data.insert(field.title, serde_json::Value::String(s));
-                        } else {
-                            form_data
-                                .insert(field.title, serde_json::Value::String("".to_string()));
-                        }
-                    } else if v.is_string() {
-                        let mut s = v.as_str().unwrap().to_string();
-                        if s.starts_with('"') && s.ends_with('"') {
-                            s = s[1..s.len() - 1].to_string();
-                        }
-                        form_data.insert(field.title, serde_json::Value::String(s));
-                    } else {
-                        form_data.insert(field.title, serde_json::Value::String("".to_string()));
-                    }
-                } else {
-                    form_data.insert(field.title, serde_json::Value::String("".to_string()));
-                }
+            s.parse::<f64>().ok()
+        }
+        _ => None,
+    };
+    let n = n.ok_or("is not a number")?;
+    if !n.is_finite() {
+        return Err("must be a finite number");
+    }
+    if integer {
+        if n.fract() != 0.0 {
+            return Err("must be a whole number");
+        }
+        // i64::MAX rounds up to 2^63 as f64, so the upper bound is exclusive
+        if n < i64::MIN as f64 || n >= i64::MAX as f64 {
+            return Err("is out of range for an integer");
+        }
+        return Ok((n as i64).into());
+    }
+    serde_json::Number::from_f64(n)
+        .map(serde_json::Value::Number)
+        .ok_or("must be a finite number")
+}
+
+/// Items of a number[] value, which the form may send as an array or a comma-separated string
+fn number_items(value: &serde_json::Value) -> Vec<serde_json::Value> {
+    match value {
+        serde_json::Value::Array(items) => items.clone(),
+        serde_json::Value::String(s) => split_list(s),
+        _ => vec![value.clone()],
+    }
+}
+
+/// Whether a saved value carries any information (blank strings and empty lists do not)
+fn has_content(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Array(arr) => !arr.is_empty(),
+        serde_json::Value::String(s) => !s.trim().is_empty(),
+        serde_json::Value::Number(_) => true,
+        serde_json::Value::Bool(_) => true,
+        serde_json::Value::Object(map) => map.values().any(has_content),
+        serde_json::Value::Null => false,
+    }
+}
+
+/// Value of `field` in `current`, falling back to the schema default
+fn field_value<'a>(
+    field: &'a FrontmatterField,
+    current: Option<&'a serde_json::Map<String, serde_json::Value>>,
+) -> Option<&'a serde_json::Value> {
+    current
+        .and_then(|map| map.get(&field.title))
+        .filter(|v| !v.is_null())
+        .or(field.default.as_ref())
+}
+
+/// Converts a frontmatter value into the shape the form editor expects for `field`
+fn form_value(field: &FrontmatterField, val: Option<&serde_json::Value>) -> serde_json::Value {
+    match field.field_type.as_str() {
+        "string[]" => match val {
+            Some(v) if v.is_array() => v.clone(),
+            Some(serde_json::Value::String(s)) => serde_json::Value::Array(split_list(s)),
+            _ => serde_json::Value::Array(vec![]),
+        },
+        "number[]" => serde_json::Value::Array(
+            val.map(number_items)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|v| to_number(v, field.integer).ok())
+                .collect(),
+        ),
+        // An unset boolean stays null so saving does not write `false` for it
+        "boolean" => val
+            .and_then(to_bool)
+            .map(serde_json::Value::Bool)
+            .unwrap_or(serde_json::Value::Null),
+        "object" => {
+            serde_json::Value::Object(form_object(&field.fields, val.and_then(|v| v.as_object())))
+        }
+        "object[]" => serde_json::Value::Array(match val {
+            Some(serde_json::Value::Array(items)) => items
+                .iter()
+                .map(|item| serde_json::Value::Object(form_object(&field.fields, item.as_object())))
+                .collect(),
+            _ => vec![],
+        }),
+        "date" | "time" | "dateandtime" => match val {
+            Some(v) if v.is_string() => v.clone(),
+            _ => serde_json::Value::Null,
+        },
+        "string" => match val {
+            // If saved as array, take the first element
+            Some(serde_json::Value::Array(items)) if !items.is_empty() => {
+                serde_json::Value::String(items[0].as_str().map(strip_quotes).unwrap_or_default())
             }
-            _ => {
-                let default_val = match field.field_type.as_str() {
-                    "number" => serde_json::Value::Number(0.into()),
-                    _ => serde_json::Value::String("".to_string()),
-                };
-                form_data.insert(field.title, val.cloned().unwrap_or(default_val));
+            Some(serde_json::Value::String(s)) => serde_json::Value::String(strip_quotes(s)),
+            _ => serde_json::Value::String("".to_string()),
+        },
+        "number" => val.cloned().unwrap_or(serde_json::Value::Number(0.into())),
+        _ => val
+            .cloned()
+            .unwrap_or(serde_json::Value::String("".to_string())),
+    }
+}
+
+fn form_object(
+    fields: &[FrontmatterField],
+    current: Option<&serde_json::Map<String, serde_json::Value>>,
+) -> serde_json::Map<String, serde_json::Value> {
+    fields
+        .iter()
+        .map(|field| {
+            (
+                field.title.clone(),
+                form_value(field, field_value(field, current)),
+            )
+        })
+        .collect()
+}
+
+/// Converts a form value back into the frontmatter value for `field`; None omits the key
+fn frontmatter_value(
+    field: &FrontmatterField,
+    raw: Option<&serde_json::Value>,
+) -> Option<serde_json::Value> {
+    let v = raw?;
+    match field.field_type.as_str() {
+        // Invalid numbers are rejected by validate_field before we get here
+        "number" => to_number(v, field.integer).ok(),
+        "number[]" => Some(serde_json::Value::Array(
+            number_items(v)
+                .iter()
+                .filter_map(|item| to_number(item, field.integer).ok())
+                .collect(),
+        )),
+        "boolean" => to_bool(v).map(serde_json::Value::Bool),
+        "string[]" => match v {
+            serde_json::Value::Array(_) => Some(v.clone()),
+            serde_json::Value::String(s) => Some(serde_json::Value::Array(split_list(s))),
+            _ => None,
+        },
+        "date" | "time" | "dateandtime" => Some(v)
+            .filter(|v| !is_empty_value(v))
+            .and_then(|v| normalize_date(field, v).ok())
+            .map(serde_json::Value::String),
+        "string" => v.as_str().map(|s| {
+            let s = if field.quote_strings {
+                format!("\"{}\"", s)
+            } else {
+                s.to_string()
+            };
+            if field.save_as_array {
+                serde_json::Value::Array(vec![serde_json::Value::String(s)])
+            } else {
+                serde_json::Value::String(s)
             }
+        }),
+        "object" => {
+            let object = frontmatter_object(&field.fields, v.as_object()?);
+            Some(object).filter(has_content)
         }
+        "object[]" => Some(serde_json::Value::Array(
+            v.as_array()?
+                .iter()
+                .filter_map(|item| item.as_object())
+                .map(|item| frontmatter_object(&field.fields, item))
+                .filter(has_content)
+                .collect(),
+        )),
+        _ => Some(v.clone()),
     }
-    Ok(serde_json::Value::Object(form_data))
+}
+
+fn frontmatter_object(
+    fields: &[FrontmatterField],
+    form: &serde_json::Map<String, serde_json::Value>,
+) -> serde_json::Value {
+    serde_json::Value::Object(
+        fields
+            .iter()
+            .filter_map(|field| {
+                frontmatter_value(field, field_value(field, Some(form)))
+                    .map(|value| (field.title.clone(), value))
+            })
+            .collect(),
+    )
+}
+
+/// The schema passed by the editor, or the folder schema of `path` when it is given
+fn resolve_schema(
+    app_handle: &tauri::AppHandle,
+    window: &tauri::Window,
+    schema: Vec<FrontmatterField>,
+    path: Option<&str>,
+) -> Result<Vec<FrontmatterField>, String> {
+    match path {
+        Some(path) => frontmatter_fields_for_path(app_handle, window, path),
+        None => Ok(schema),
+    }
+}
+
+#[tauri::command]
+pub fn initialize_form_data(
+    app_handle: tauri::AppHandle,
+    window: tauri::Window,
+    schema: Vec<FrontmatterField>,
+    current_frontmatter: serde_json::Value,
+    path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let schema = resolve_schema(&app_handle, &window, schema, path.as_deref())?;
+    // Fields the post does not set start from the schema default
+    Ok(serde_json::Value::Object(form_object(
+        &schema,
+        current_frontmatter.as_object(),
+    )))
 }
 
 #[tauri::command]
 pub fn save_form_data_to_frontmatter(
+    app_handle: tauri::AppHandle,
+    window: tauri::Window,
     schema: Vec<FrontmatterField>,
     form_data: serde_json::Value,
+    path: Option<String>,
 ) -> Result<serde_json::Value, String> {
-    let form: HashMap<String, serde_json::Value> =
-        serde_json::from_value(form_data).unwrap_or_default();
-    let mut output = serde_json::Map::new();
-
-    for field in schema {
-        let raw = form.get(&field.title);
-        match field.field_type.as_str() {
-            "number" => {
-                if let Some(v) = raw {
-                    if let Some(n) = v.as_f64() {
-                        output.insert(field.title, serde_json::Value::Number((n as i64).into()));
-                    }
-                }
-            }
-            "string[]" => {
-                if let Some(v) = raw {
-                    if v.is_array() {
-                        output.insert(field.title, v.clone());
-                    } else if v.is_string() {
-                        let arr: Vec<serde_json::Value> = v
-                            .as_str()
-                            .unwrap()
-                            .split(',')
-                            .map(|s| serde_json::Value::String(s.trim().to_string()))
-                            .filter(|s| !s.as_str().unwrap().is_empty())
-                            .collect();
-                        output.insert(field.title, serde_json::Value::Array(arr));
-                    }
-                }
-            }
-            "date" => {
-                if let Some(v) = raw {
-                    if v.is_string() {
-                        let date_str = v.as_str().unwrap();
-                        if !date_str.is_empty() {
-                            output.insert(
-                                field.title,
-                                serde_json::Value::String(date_str.to_string()),
-                            );
-                        }
-                    }
-                }
-            }
-            "time" => {
-                if let Some(v) = raw {
-                    if v.is_string() {
-                        let time_str = v.as_str().unwrap();
-                        if !time_str.is_empty() {
-                            output.insert(
-                                field.title,
-                                serde_json::Value::String(time_str.to_string()),
-                            );
-                        }
-                    }
-                }
+    let schema = resolve_schema(&app_handle, &window, schema, path.as_deref())?;
+    let form = form_data.as_object().cloned().unwrap_or_default();
+
+    let errors: Vec<String> = schema
+        .iter()
+        .flat_map(|field| validate_field(field, field_value(field, Some(&form)), None))
+        .collect();
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+
+    let output = frontmatter_object(&schema, &form);
+    if has_content(&output) {
+        Ok(output)
+    } else {
+        Ok(serde_json::Value::Object(serde_json::Map::new()))
+    }
+}
+
+fn is_empty_value(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Null => true,
+        serde_json::Value::String(s) => s.trim().is_empty(),
+        serde_json::Value::Array(items) => items.is_empty(),
+        _ => false,
+    }
+}
+
+/// Checks a form value against the field's required flag, bounds, pattern and allowed values.
+/// Nested fields of objects are reported as `parent.child`.
+fn validate_field(
+    field: &FrontmatterField,
+    value: Option<&serde_json::Value>,
+    parent: Option<&str>,
+) -> Vec<String> {
+    let name = match parent {
+        Some(parent) => format!("{}.{}", parent, field.display_name()),
+        None => field.display_name().to_string(),
+    };
+    let name = name.as_str();
+    let Some(value) = value.filter(|v| !is_empty_value(v)) else {
+        if field.required {
+            return vec![format!("\"{}\" is required", name)];
+        }
+        return Vec::new();
+    };
+
+    let mut errors = Vec::new();
+    let objects: Vec<&serde_json::Map<String, serde_json::Value>> = match field.field_type.as_str()
+    {
+        "object" => value.as_object().into_iter().collect(),
+        "object[]" => value
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|item| item.as_object())
+            .collect(),
+        _ => Vec::new(),
+    };
+    for object in objects {
+        for nested in &field.fields {
+            errors.extend(validate_field(
+                nested,
+                field_value(nested, Some(object)),
+                Some(name),
+            ));
+        }
+    }
+
+    if field.field_type == "number" || field.field_type == "number[]" {
+        let mut numbers = Vec::new();
+        for item in number_items(value) {
+            match to_number(&item, field.integer) {
+                Ok(n) => numbers.extend(n.as_f64()),
+                Err(reason) => errors.push(format!("\"{}\" {}", name, reason)),
             }
-            "dateandtime" => {
-                if let Some(v) = raw {
-                    if v.is_string() {
-                        let datetime_str = v.as_str().unwrap();
-                        if !datetime_str.is_empty() {
-                            output.insert(
-                                field.title,
-                                serde_json::Value::String(datetime_str.to_string()),
-                            );
-                        }
-                    }
-                }
+        }
+        for n in numbers {
+            if field.min.is_some_and(|min| n < min) || field.max.is_some_and(|max| n > max) {
+                let range = match (field.min, field.max) {
+                    (Some(min), Some(max)) => format!("between {} and {}", min, max),
+                    (Some(min), None) => format!("at least {}", min),
+                    (None, Some(max)) => format!("at most {}", max),
+                    (None, None) => continue,
+                };
+                errors.push(format!("\"{}\" must be {}", name, range));
             }
-            "string" => {
-                if let Some(v) = raw {
-                    if v.is_string() {
-                        let mut s = v.as_str().unwrap().to_string();
-                        if field.quote_strings {
-                            s = format!("\"{}\"", s);
-                        }
-                        if field.save_as_array {
-                            output.insert(
-                                field.title,
-                                serde_json::Value::Array(vec![serde_json::Value::String(s)]),
-                            );
-                        } else {
-                            output.insert(field.title, serde_json::Value::String(s));
-                        }
+        }
+    }
+
+    if matches!(field.field_type.as_str(), "date" | "time" | "dateandtime") {
+        if let Err(e) = normalize_date(field, value) {
+            errors.push(format!("{} in \"{}\"", e, name));
+        }
+    }
+
+    let items: Vec<String> = match value {
+        serde_json::Value::Array(items) => items.iter().filter_map(json_text).collect(),
+        _ => json_text(value).into_iter().collect(),
+    };
+    if let Some(pattern) = field.pattern.as_deref().filter(|p| !p.is_empty()) {
+        if field.field_type == "string" || field.field_type == "string[]" {
+            match regex::Regex::new(pattern) {
+                Ok(regex) => {
+                    for item in items.iter().filter(|item| !regex.is_match(item)) {
+                        errors.push(format!(
+                            "\"{}\" does not match the pattern for \"{}\"",
+                            item, name
+                        ));
                     }
                 }
-            }
-            _ => {
-                if let Some(v) = raw {
-                    output.insert(field.title, v.clone());
-                }
+                Err(e) => errors.push(format!("Invalid pattern for \"{}\": {}", name, e)),
             }
         }
     }
+    if !field.allowed_values.is_empty() {
+        for item in items
+            .iter()
+            .filter(|item| !field.allowed_values.contains(item))
+        {
+            errors.push(format!(
+                "\"{}\" is not an allowed value for \"{}\"",
+                item, name
+            ));
+        }
+    }
+    errors
+}
 
-    let has_content = output.values().any(|v| match v {
-        serde_json::Value::Array(arr) => !arr.is_empty(),
-        serde_json::Value::String(s) => !s.trim().is_empty(),
-        serde_json::Value::Number(_) => true,
-        _ => false,
-    });
-
-    if has_content {
-        Ok(serde_json::Value::Object(output))
-    } else {
-        Ok(serde_json::Value::Object(serde_json::Map::new()))
+pub(crate) fn json_text(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        serde_json::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
     }
 }
 
-fn extract_frontmatter(content: &str) -> Option<HashMap<String, serde_yaml::Value>> {
+pub(crate) fn extract_frontmatter(content: &str) -> Option<HashMap<String, serde_yaml::Value>> {
     if content.starts_with("---") {
         if let Some(end) = content[3..].find("---") {
             let yaml_str = &content[3..3 + end];
@@ -406,3 +692,75 @@ fn extract_frontmatter(content: &str) -> Option<HashMap<String, serde_yaml::Valu
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn field(title: &str, field_type: &str) -> FrontmatterField {
+        FrontmatterField {
+            title: title.to_string(),
+            field_type: field_type.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn unset_boolean_is_left_out() {
+        let published = field("published", "boolean");
+        let value = form_value(&published, field_value(&published, None));
+        assert_eq!(value, serde_json::Value::Null);
+        assert_eq!(frontmatter_value(&published, Some(&value)), None);
+
+        let with_default = FrontmatterField {
+            default: Some(json!(true)),
+            ..published
+        };
+        assert_eq!(
+            form_value(&with_default, field_value(&with_default, None)),
+            json!(true)
+        );
+    }
+
+    #[test]
+    fn false_boolean_is_kept() {
+        let hidden = field("hidden", "boolean");
+        let cover = FrontmatterField {
+            fields: vec![hidden.clone()],
+            ..field("cover", "object")
+        };
+        assert_eq!(
+            frontmatter_value(&hidden, Some(&json!("no"))),
+            Some(json!(false))
+        );
+        assert_eq!(
+            frontmatter_value(&cover, Some(&json!({ "hidden": false }))),
+            Some(json!({ "hidden": false }))
+        );
+        assert!(has_content(&json!({ "draft": false })));
+        assert!(!has_content(&json!({ "tags": [], "title": " " })));
+    }
+
+    #[test]
+    fn range_errors_name_the_bounds_that_are_set() {
+        let bounded = |min, max| FrontmatterField {
+            min,
+            max,
+            ..field("weight", "number")
+        };
+        assert_eq!(
+            validate_field(&bounded(Some(1.0), Some(10.0)), Some(&json!(11)), None),
+            vec!["\"weight\" must be between 1 and 10"]
+        );
+        assert_eq!(
+            validate_field(&bounded(None, Some(10.0)), Some(&json!(11)), None),
+            vec!["\"weight\" must be at most 10"]
+        );
+        assert_eq!(
+            validate_field(&bounded(Some(1.0), None), Some(&json!("0")), None),
+            vec!["\"weight\" must be at least 1"]
+        );
+        assert!(validate_field(&bounded(Some(1.0), None), Some(&json!(5)), None).is_empty());
+    }
+}
@@ -1,10 +1,16 @@
+use super::auto_fields::apply_auto_fields;
+use super::frontmatter_dates::quote_date_strings;
+use super::save_frontmatter::frontmatter_fields_for_path;
+use super::workspace_index::notify_path_changed;
 use serde_json;
 use serde_yaml;
 use std::collections::HashMap;
+use std::io::Write;
 use std::{fs, path::Path};
+use tauri::{AppHandle, Window};
 
 #[tauri::command]
-pub fn save_markdown(file_path: String, content: String) -> Result<(), String> {
+pub fn save_markdown(app: AppHandle, file_path: String, content: String) -> Result<(), String> {
     let path = Path::new(&file_path);
     if let Some(parent) = path.parent() {
         if !parent.exists() {
@@ -13,14 +19,46 @@ pub fn save_markdown(file_path: String, content: String) -> Result<(), String> {
         }
     }
 
-    fs::write(path, content).map_err(|e| format!("Failed to write file: {}", e))?;
+    write_atomic(path, &content)?;
+    notify_path_changed(&app, path);
 
     Ok(())
 }
 
+/// Writes `content` to a temporary file next to `path` and renames it over the
+/// target, so a crash or full disk never leaves a half-written post behind.
+/// Symlinks are resolved first so the link itself is kept, and an existing
+/// file's permissions are copied to the replacement.
+pub(crate) fn write_atomic(path: &Path, content: &str) -> Result<(), String> {
+    let target = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let file_name = target
+        .file_name()
+        .ok_or_else(|| format!("Invalid file path: {}", path.display()))?;
+    let tmp_path = target.with_file_name(format!(".{}.rsoul-tmp", file_name.to_string_lossy()));
+    let permissions = fs::metadata(&target).ok().map(|m| m.permissions());
+
+    let result = (|| -> std::io::Result<()> {
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        if let Some(permissions) = permissions {
+            fs::set_permissions(&tmp_path, permissions)?;
+        }
+        fs::rename(&tmp_path, &target)
+    })();
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(format!("Failed to write file: {}", e));
+    }
+    Ok(())
+}
+
 #[tauri::command]
 pub fn save_markdown_with_frontmatter(
-    frontmatter: HashMap<String, serde_json::Value>,
+    app: AppHandle,
+    window: Window,
+    mut frontmatter: HashMap<String, serde_json::Value>,
     content: String,
     file_path: String,
 ) -> Result<(), String> {
@@ -34,12 +72,68 @@ pub fn save_markdown_with_frontmatter(
     });
 
     let full_content = if has_content {
+        let fields = frontmatter_fields_for_path(&app, &window, &file_path)?;
+        // Fill in lastmod, slug, word count and similar fields configured in the schema
+        let previous = fs::read_to_string(&file_path).ok();
+        apply_auto_fields(&fields, &mut frontmatter, &content, previous.as_deref())?;
         let yaml_str = serde_yaml::to_string(&frontmatter)
             .map_err(|e| format!("YAML serialization error: {}", e))?;
+        // Dates configured as strings are quoted so they are not read as YAML timestamps
+        let yaml_str = quote_date_strings(&yaml_str, &fields, &frontmatter);
         format!("---\n{}---\n\n{}", yaml_str, content)
     } else {
         content
     };
 
-    save_markdown(file_path, full_content)
+    save_markdown(app, file_path, full_content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_atomic_replaces_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("post.md");
+        write_atomic(&path, "new file").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new file");
+        write_atomic(&path, "updated").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "updated");
+        // The temporary file is renamed away
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_atomic_keeps_symlinked_posts() {
+        let dir = tempfile::tempdir().unwrap();
+        let real = dir.path().join("real.md");
+        let link = dir.path().join("link.md");
+        fs::write(&real, "old").unwrap();
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+
+        write_atomic(&link, "new").unwrap();
+        assert!(fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(fs::read_to_string(&real).unwrap(), "new");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_atomic_keeps_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("private.md");
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+
+        write_atomic(&path, "new").unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+    }
 }
@@ -0,0 +1,324 @@
+use super::frontmatter_dates::check_date_settings;
+use super::save_frontmatter::{frontmatter_fields, store_frontmatter_fields, FrontmatterField};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, Window};
+
+/// 支持的字段类型
+pub(crate) const FIELD_TYPES: &[&str] = &[
+    "string",
+    "string[]",
+    "number",
+    "number[]",
+    "boolean",
+    "date",
+    "time",
+    "dateandtime",
+    "object",
+    "object[]",
+];
+
+/// 导入时与现有字段定义的合并方式
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    /// 同名字段以导入的定义为准，其余字段保留
+    #[default]
+    Merge,
+    /// 用导入的定义替换全部字段
+    Replace,
+}
+
+/// 字段定义文件的内容。也接受 `.rsoul/config.toml` 中的 `frontmatter_fields`
+#[derive(Serialize, Deserialize)]
+struct SchemaFile {
+    #[serde(alias = "frontmatter_fields")]
+    fields: Vec<FrontmatterField>,
+}
+
+#[derive(Clone, Copy)]
+enum SchemaFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+fn schema_format(path: &Path) -> Result<SchemaFormat, String> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("json") => Ok(SchemaFormat::Json),
+        Some("yaml" | "yml") => Ok(SchemaFormat::Yaml),
+        Some("toml") => Ok(SchemaFormat::Toml),
+        _ => Err(format!(
+            "Unsupported schema file {}: expected .json, .yaml, .yml or .toml",
+            path.display()
+        )),
+    }
+}
+
+fn serialize_schema(fields: Vec<FrontmatterField>, format: SchemaFormat) -> Result<String, String> {
+    let file = SchemaFile { fields };
+    match format {
+        SchemaFormat::Json => serde_json::to_string_pretty(&file)
+            .map_err(|e| format!("JSON serialization error: {}", e)),
+        SchemaFormat::Yaml => {
+            serde_yaml::to_string(&file).map_err(|e| format!("YAML serialization error: {}", e))
+        }
+        SchemaFormat::Toml => {
+            toml::to_string_pretty(&file).map_err(|e| format!("TOML serialization error: {}", e))
+        }
+    }
+}
+
+/// 解析字段定义文件；JSON 与 YAML 也可以直接是字段数组
+fn parse_schema(content: &str, format: SchemaFormat) -> Result<Vec<FrontmatterField>, String> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Document {
+        File(SchemaFile),
+        Fields(Vec<FrontmatterField>),
+    }
+
+    let document = match format {
+        SchemaFormat::Json => serde_json::from_str::<Document>(content).map_err(|e| e.to_string()),
+        SchemaFormat::Yaml => serde_yaml::from_str::<Document>(content).map_err(|e| e.to_string()),
+        SchemaFormat::Toml => toml::from_str::<SchemaFile>(content)
+            .map(Document::File)
+            .map_err(|e| e.to_string()),
+    }
+    .map_err(|e| format!("Invalid schema file: {}", e))?;
+
+    Ok(match document {
+        Document::File(file) => file.fields,
+        Document::Fields(fields) => fields,
+    })
+}
+
+fn check_fields(fields: &[FrontmatterField], parent: Option<&str>, errors: &mut Vec<String>) {
+    let mut titles = HashSet::new();
+    for field in fields {
+        let name = match parent {
+            Some(parent) => format!("{}.{}", parent, field.title),
+            None => field.title.clone(),
+        };
+        if field.title.trim().is_empty() {
+            errors.push(match parent {
+                Some(parent) => format!("A field in \"{}\" has no name", parent),
+                None => "A field has no name".to_string(),
+            });
+            continue;
+        }
+        if !titles.insert(field.title.as_str()) {
+            errors.push(format!("Field \"{}\" is defined more than once", name));
+        }
+        if !FIELD_TYPES.contains(&field.field_type.as_str()) {
+            errors.push(format!(
+                "Field \"{}\" has unknown type \"{}\"",
+                name, field.field_type
+            ));
+        }
+        if let (Some(min), Some(max)) = (field.min, field.max) {
+            if min > max {
+                errors.push(format!("Field \"{}\" has min greater than max", name));
+            }
+        }
+        if let Some(pattern) = field.pattern.as_deref().filter(|p| !p.is_empty()) {
+            if let Err(e) = regex::Regex::new(pattern) {
+                errors.push(format!("Invalid pattern for \"{}\": {}", name, e));
+            }
+        }
+        if matches!(field.field_type.as_str(), "date" | "time" | "dateandtime") {
+            if let Err(e) = check_date_settings(field) {
+                errors.push(e);
+            }
+        }
+        if field.field_type == "object" || field.field_type == "object[]" {
+            check_fields(&field.fields, Some(&name), errors);
+        } else if !field.fields.is_empty() {
+            errors.push(format!(
+                "Field \"{}\" has nested fields but is not an object",
+                name
+            ));
+        }
+    }
+}
+
+/// 检查字段定义：名称非空且不重复、类型有效、范围、正则与日期配置正确
+pub(crate) fn validate_schema(fields: &[FrontmatterField]) -> Result<(), String> {
+    let mut errors = Vec::new();
+    check_fields(fields, None, &mut errors);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("\n"))
+    }
+}
+
+/// 按 `mode` 合并字段定义，并按顺序重新编号
+pub(crate) fn merge_schema(
+    current: Vec<FrontmatterField>,
+    imported: Vec<FrontmatterField>,
+    mode: ImportMode,
+) -> Vec<FrontmatterField> {
+    let mut fields = match mode {
+        ImportMode::Replace => imported,
+        ImportMode::Merge => {
+            let mut fields = current;
+            for field in imported {
+                match fields.iter_mut().find(|f| f.title == field.title) {
+                    Some(existing) => *existing = field,
+                    None => fields.push(field),
+                }
+            }
+            fields
+        }
+    };
+    for (key, field) in fields.iter_mut().enumerate() {
+        field.key = key;
+    }
+    fields
+}
+
+/// Tauri 命令：将当前的字段定义导出为 JSON、YAML 或 TOML 文件（按扩展名判断格式）。
+#[tauri::command]
+pub fn export_frontmatter_schema(
+    app: AppHandle,
+    window: Window,
+    path: String,
+) -> Result<(), String> {
+    let path = Path::new(&path);
+    let format = schema_format(path)?;
+    let content = serialize_schema(frontmatter_fields(&app, &window)?, format)?;
+    fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Tauri 命令：从 JSON、YAML 或 TOML 文件导入字段定义，校验后与现有定义合并或替换，
+/// 返回导入后的字段定义。
+#[tauri::command]
+pub fn import_frontmatter_schema(
+    app: AppHandle,
+    window: Window,
+    path: String,
+    mode: Option<ImportMode>,
+) -> Result<Vec<FrontmatterField>, String> {
+    let path = Path::new(&path);
+    let format = schema_format(path)?;
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let imported = parse_schema(&content, format)?;
+    validate_schema(&imported)?;
+
+    let fields = merge_schema(
+        frontmatter_fields(&app, &window)?,
+        imported,
+        mode.unwrap_or_default(),
+    );
+    store_frontmatter_fields(&app, &window, fields.clone())?;
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(title: &str, field_type: &str) -> FrontmatterField {
+        FrontmatterField {
+            title: title.to_string(),
+            field_type: field_type.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn titles(fields: &[FrontmatterField]) -> Vec<(usize, &str, &str)> {
+        fields
+            .iter()
+            .map(|f| (f.key, f.title.as_str(), f.field_type.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn merge_schema_replaces_same_titles_and_keeps_the_rest() {
+        let current = vec![field("title", "string"), field("tags", "string")];
+        let imported = vec![field("tags", "string[]"), field("draft", "boolean")];
+        assert_eq!(
+            titles(&merge_schema(
+                current.clone(),
+                imported.clone(),
+                ImportMode::Merge
+            )),
+            vec![
+                (0, "title", "string"),
+                (1, "tags", "string[]"),
+                (2, "draft", "boolean")
+            ]
+        );
+        assert_eq!(
+            titles(&merge_schema(current, imported, ImportMode::Replace)),
+            vec![(0, "tags", "string[]"), (1, "draft", "boolean")]
+        );
+    }
+
+    #[test]
+    fn schema_round_trips_through_every_format() {
+        let fields = vec![
+            field("title", "string"),
+            FrontmatterField {
+                fields: vec![field("image", "string")],
+                ..field("cover", "object")
+            },
+        ];
+        for format in [SchemaFormat::Json, SchemaFormat::Yaml, SchemaFormat::Toml] {
+            let content = serialize_schema(fields.clone(), format).unwrap();
+            let parsed = parse_schema(&content, format).unwrap();
+            assert_eq!(titles(&parsed), titles(&fields));
+            assert_eq!(titles(&parsed[1].fields), vec![(0, "image", "string")]);
+        }
+        let bare = r#"[{"title": "tags", "field_type": "string[]"}]"#;
+        assert_eq!(
+            titles(&parse_schema(bare, SchemaFormat::Json).unwrap()),
+            vec![(0, "tags", "string[]")]
+        );
+    }
+
+    #[test]
+    fn validate_schema_reports_every_problem() {
+        let errors = validate_schema(&[
+            field("title", "string"),
+            field("title", "text"),
+            FrontmatterField {
+                min: Some(5.0),
+                max: Some(1.0),
+                ..field("weight", "number")
+            },
+            FrontmatterField {
+                fields: vec![field("", "string")],
+                ..field("cover", "object")
+            },
+        ])
+        .unwrap_err();
+        assert_eq!(
+            errors.lines().collect::<Vec<_>>(),
+            vec![
+                "Field \"title\" is defined more than once",
+                "Field \"title\" has unknown type \"text\"",
+                "Field \"weight\" has min greater than max",
+                "A field in \"cover\" has no name",
+            ]
+        );
+        assert!(validate_schema(&[field("title", "string")]).is_ok());
+    }
+
+    #[test]
+    fn schema_format_follows_the_extension() {
+        assert!(matches!(
+            schema_format(Path::new("schema.YML")),
+            Ok(SchemaFormat::Yaml)
+        ));
+        assert!(schema_format(Path::new("schema.txt")).is_err());
+    }
+}
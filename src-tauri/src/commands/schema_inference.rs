@@ -0,0 +1,249 @@
+use super::save_frontmatter::FrontmatterField;
+use super::workspace_config::require_workspace;
+use super::workspace_index::{markdown_frontmatter, scalar_value};
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::{AppHandle, Window};
+
+/// 推断出的单个字段
+#[derive(Serialize)]
+pub struct InferredField {
+    /// 建议的字段定义，可直接传给 `save_frontmatter`
+    pub field: FrontmatterField,
+    /// 使用该字段的文章数
+    pub usage: usize,
+    /// 使用该字段的文章占全部文章的比例
+    pub frequency: f64,
+    /// 各类型出现的次数（string、string[]、number、number[]、boolean、date、time、dateandtime、object、object[]）
+    pub type_counts: HashMap<String, usize>,
+    /// 值写成数组的比例
+    pub array_ratio: f64,
+    /// 字符串值带有引号的比例
+    pub quoted_ratio: f64,
+}
+
+#[derive(Serialize)]
+pub struct InferredSchema {
+    /// 有 frontmatter 的文章数
+    pub total_posts: usize,
+    pub fields: Vec<InferredField>,
+}
+
+#[derive(Default)]
+struct FieldStats {
+    usage: usize,
+    type_counts: HashMap<String, usize>,
+    arrays: usize,
+    /// 含有多个元素的数组个数
+    multi_item_arrays: usize,
+    strings: usize,
+    quoted: usize,
+    /// 带小数的数字个数
+    floats: usize,
+    /// `object` / `object[]` 字段中各子字段的统计
+    nested: HashMap<String, FieldStats>,
+}
+
+impl FieldStats {
+    fn add_scalar(&mut self, value: &serde_yaml::Value) -> Option<&'static str> {
+        let (text, value_type) = scalar_value(value)?;
+        if value_type == "string" {
+            self.strings += 1;
+            // 开启 quote_strings 时保存的值本身带有双引号
+            if text.len() >= 2 && text.starts_with('"') && text.ends_with('"') {
+                self.quoted += 1;
+            }
+        }
+        if value.is_f64() {
+            self.floats += 1;
+        }
+        Some(value_type)
+    }
+
+    fn add_nested(&mut self, mapping: &serde_yaml::Mapping) {
+        for (key, value) in mapping {
+            if let (Some(key), false) = (key.as_str(), value.is_null()) {
+                self.nested.entry(key.to_string()).or_default().add(value);
+            }
+        }
+    }
+
+    fn add(&mut self, value: &serde_yaml::Value) {
+        self.usage += 1;
+        let value_type = match value {
+            serde_yaml::Value::Mapping(mapping) => {
+                self.add_nested(mapping);
+                "object"
+            }
+            serde_yaml::Value::Sequence(items)
+                if !items.is_empty() && items.iter().all(|item| item.is_mapping()) =>
+            {
+                self.arrays += 1;
+                for item in items.iter().filter_map(|item| item.as_mapping()) {
+                    self.add_nested(item);
+                }
+                "object[]"
+            }
+            serde_yaml::Value::Sequence(items) => {
+                self.arrays += 1;
+                if items.len() > 1 {
+                    self.multi_item_arrays += 1;
+                }
+                let item_types: Vec<&'static str> = items
+                    .iter()
+                    .filter_map(|item| self.add_scalar(item))
+                    .collect();
+                let all_numbers = !item_types.is_empty()
+                    && item_types.len() == items.len()
+                    && item_types.iter().all(|t| *t == "number");
+                match item_types.first() {
+                    Some(&"string") | None => "string[]",
+                    Some(_) if all_numbers && items.len() > 1 => "number[]",
+                    // 单元素的非字符串数组按元素类型计
+                    Some(&first) if items.len() == 1 => first,
+                    Some(_) => "string[]",
+                }
+            }
+            _ => match self.add_scalar(value) {
+                Some(value_type) => value_type,
+                None => return,
+            },
+        };
+        *self.type_counts.entry(value_type.to_string()).or_insert(0) += 1;
+    }
+
+    /// 出现次数最多的类型；日期与时间混用时取 dateandtime，其余冲突退回 string
+    fn field_type(&self) -> String {
+        let Some((top, top_count)) = self
+            .type_counts
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+        else {
+            return "string".to_string();
+        };
+        let total: usize = self.type_counts.values().sum();
+        if *top_count == total {
+            return top.clone();
+        }
+        let all_dates = self
+            .type_counts
+            .keys()
+            .all(|t| matches!(t.as_str(), "date" | "dateandtime"));
+        if all_dates {
+            return "dateandtime".to_string();
+        }
+        // 少数不同类型的值（如个别文章把数字写成字符串）不影响结果
+        if *top_count * 10 >= total * 9 {
+            return top.clone();
+        }
+        "string".to_string()
+    }
+}
+
+fn ratio(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+/// 根据统计结果生成字段定义，`object` 与 `object[]` 字段递归推断子字段
+fn infer_field(key: usize, title: String, s: &FieldStats) -> FrontmatterField {
+    let mut field_type = s.field_type();
+    let array_ratio = ratio(s.arrays, s.usage);
+    let quoted_ratio = ratio(s.quoted, s.strings);
+    let mut save_as_array = false;
+    let only_strings = s
+        .type_counts
+        .keys()
+        .all(|t| t == "string" || t == "string[]");
+    if only_strings && s.arrays > 0 {
+        if s.multi_item_arrays > 0 || s.arrays == s.usage {
+            field_type = "string[]".to_string();
+        } else {
+            // 单个字符串与单元素数组混用：按单值字段处理
+            field_type = "string".to_string();
+            save_as_array = array_ratio > 0.5;
+        }
+    }
+    let quote_strings = field_type == "string" && quoted_ratio > 0.5;
+    let integer = (field_type == "number" || field_type == "number[]") && s.floats == 0;
+
+    let mut nested: Vec<(&String, &FieldStats)> = s.nested.iter().collect();
+    nested.sort_by(|a, b| b.1.usage.cmp(&a.1.usage).then_with(|| a.0.cmp(b.0)));
+    let fields = if field_type == "object" || field_type == "object[]" {
+        nested
+            .into_iter()
+            .filter(|(_, stats)| !stats.type_counts.is_empty())
+            .enumerate()
+            .map(|(key, (title, stats))| infer_field(key, title.clone(), stats))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    FrontmatterField {
+        key,
+        title,
+        field_type,
+        save_as_array,
+        quote_strings,
+        integer,
+        fields,
+        ..Default::default()
+    }
+}
+
+/// Tauri 命令：扫描当前窗口的工作区，根据已有文章推断 frontmatter 字段定义。
+/// 单个字符串与单元素数组混用的字段推断为 `string`，多数写成数组时开启 `save_as_array`。
+#[tauri::command]
+pub async fn infer_frontmatter_schema(
+    app: AppHandle,
+    window: Window,
+    min_frequency: Option<f64>,
+) -> Result<InferredSchema, String> {
+    let workspace = require_workspace(&window)?;
+    let files =
+        tauri::async_runtime::spawn_blocking(move || markdown_frontmatter(&app, &workspace))
+            .await
+            .map_err(|e| format!("Background thread failed: {}", e))??;
+
+    let mut total_posts = 0;
+    let mut stats: HashMap<String, FieldStats> = HashMap::new();
+    for frontmatter in files.iter().filter_map(|(_, fm)| fm.as_deref()) {
+        total_posts += 1;
+        for (key, value) in frontmatter {
+            if value.is_null() {
+                continue;
+            }
+            stats.entry(key.clone()).or_default().add(value);
+        }
+    }
+
+    let min_frequency = min_frequency.unwrap_or(0.0);
+    let mut fields: Vec<(String, FieldStats)> = stats
+        .into_iter()
+        .filter(|(_, s)| !s.type_counts.is_empty())
+        .filter(|(_, s)| ratio(s.usage, total_posts) >= min_frequency)
+        .collect();
+    fields.sort_by(|a, b| b.1.usage.cmp(&a.1.usage).then_with(|| a.0.cmp(&b.0)));
+
+    let fields = fields
+        .into_iter()
+        .enumerate()
+        .map(|(key, (title, s))| InferredField {
+            field: infer_field(key, title, &s),
+            usage: s.usage,
+            frequency: ratio(s.usage, total_posts),
+            array_ratio: ratio(s.arrays, s.usage),
+            quoted_ratio: ratio(s.quoted, s.strings),
+            type_counts: s.type_counts,
+        })
+        .collect();
+
+    Ok(InferredSchema {
+        total_posts,
+        fields,
+    })
+}
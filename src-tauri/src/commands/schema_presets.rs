@@ -0,0 +1,330 @@
+use super::save_frontmatter::{
+    frontmatter_fields, store_frontmatter_fields, AutoValue, FrontmatterField,
+};
+use super::schema_files::{merge_schema, ImportMode};
+use super::workspace_config::require_workspace;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, Window};
+
+/// 内置的静态站点生成器字段预设
+#[derive(Serialize)]
+pub struct SchemaPreset {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub fields: Vec<FrontmatterField>,
+}
+
+/// 根据工作区根目录中的文件识别出的站点生成器
+#[derive(Serialize)]
+pub struct DetectedPreset {
+    /// 对应的预设 id
+    pub preset: &'static str,
+    /// 识别依据的文件（相对工作区根目录）
+    pub marker: String,
+}
+
+/// 预设 id、名称与识别用的文件；文件按顺序检查，命中任意一个即识别为该生成器
+const GENERATORS: &[(&str, &str, &[&str])] = &[
+    (
+        "docusaurus",
+        "Docusaurus",
+        &[
+            "docusaurus.config.js",
+            "docusaurus.config.ts",
+            "docusaurus.config.mjs",
+        ],
+    ),
+    (
+        "vitepress",
+        "VitePress",
+        &[
+            ".vitepress/config.js",
+            ".vitepress/config.ts",
+            ".vitepress/config.mjs",
+            ".vitepress/config.mts",
+            "docs/.vitepress/config.js",
+            "docs/.vitepress/config.ts",
+            "docs/.vitepress/config.mjs",
+            "docs/.vitepress/config.mts",
+        ],
+    ),
+    (
+        "astro",
+        "Astro",
+        &[
+            "astro.config.mjs",
+            "astro.config.js",
+            "astro.config.ts",
+            "astro.config.mts",
+            "astro.config.cjs",
+        ],
+    ),
+    (
+        "hugo",
+        "Hugo",
+        &[
+            "hugo.toml",
+            "hugo.yaml",
+            "hugo.yml",
+            "hugo.json",
+            "archetypes",
+        ],
+    ),
+    ("hexo", "Hexo", HEXO_MARKERS),
+    ("jekyll", "Jekyll", &["Gemfile", "_config.yml"]),
+];
+
+/// Hexo 的识别文件。Hexo 与 Jekyll 都使用 `_config.yml`，有这些文件时不识别为 Jekyll
+const HEXO_MARKERS: &[&str] = &["scaffolds", "_config.landscape.yml", "package.json"];
+
+/// 较通用的识别文件，在 `GENERATORS` 都未命中后才检查：
+/// 旧版 Hugo 使用 `config.toml` 等文件名与 `config/_default/` 目录
+const FALLBACK_MARKERS: &[(&str, &[&str])] = &[(
+    "hugo",
+    &[
+        "config.toml",
+        "config.yaml",
+        "config.yml",
+        "config.json",
+        "config/_default",
+    ],
+)];
+
+fn field(title: &str, field_type: &str) -> FrontmatterField {
+    FrontmatterField {
+        title: title.to_string(),
+        field_type: field_type.to_string(),
+        ..Default::default()
+    }
+}
+
+fn auto(mut field: FrontmatterField, value: AutoValue) -> FrontmatterField {
+    field.auto = Some(value);
+    field
+}
+
+fn required(mut field: FrontmatterField) -> FrontmatterField {
+    field.required = true;
+    field
+}
+
+fn date_format(mut field: FrontmatterField, format: &str) -> FrontmatterField {
+    field.date_format = Some(format.to_string());
+    field
+}
+
+fn integer(mut field: FrontmatterField) -> FrontmatterField {
+    field.integer = true;
+    field
+}
+
+fn preset_fields(id: &str) -> Vec<FrontmatterField> {
+    let fields = match id {
+        "hugo" => vec![
+            required(field("title", "string")),
+            required(field("date", "dateandtime")),
+            auto(field("lastmod", "dateandtime"), AutoValue::LastModified),
+            field("draft", "boolean"),
+            field("description", "string"),
+            field("tags", "string[]"),
+            field("categories", "string[]"),
+            auto(field("slug", "string"), AutoValue::Slug),
+            integer(field("weight", "number")),
+        ],
+        // Hexo 的日期写法为 `YYYY-MM-DD HH:mm:ss`
+        "hexo" => vec![
+            required(field("title", "string")),
+            date_format(field("date", "dateandtime"), "%Y-%m-%d %H:%M:%S"),
+            auto(
+                date_format(field("updated", "dateandtime"), "%Y-%m-%d %H:%M:%S"),
+                AutoValue::LastModified,
+            ),
+            field("tags", "string[]"),
+            field("categories", "string[]"),
+            field("permalink", "string"),
+            field("comments", "boolean"),
+            field("excerpt", "string"),
+        ],
+        // Jekyll 的日期写法为 `YYYY-MM-DD HH:MM:SS +/-TTTT`
+        "jekyll" => vec![
+            field("layout", "string"),
+            required(field("title", "string")),
+            date_format(field("date", "dateandtime"), "%Y-%m-%d %H:%M:%S %z"),
+            field("categories", "string[]"),
+            field("tags", "string[]"),
+            field("permalink", "string"),
+            field("published", "boolean"),
+            field("excerpt", "string"),
+        ],
+        // Astro 博客模板中 content collection 的字段
+        "astro" => vec![
+            required(field("title", "string")),
+            required(field("description", "string")),
+            required(field("pubDate", "date")),
+            auto(field("updatedDate", "date"), AutoValue::LastModified),
+            field("heroImage", "string"),
+            field("tags", "string[]"),
+            field("draft", "boolean"),
+        ],
+        "vitepress" => vec![
+            field("title", "string"),
+            field("titleTemplate", "string"),
+            field("description", "string"),
+            FrontmatterField {
+                allowed_values: vec!["doc".into(), "home".into(), "page".into()],
+                ..field("layout", "string")
+            },
+            field("sidebar", "boolean"),
+            field("aside", "boolean"),
+            field("outline", "string"),
+            field("lastUpdated", "boolean"),
+            field("editLink", "boolean"),
+        ],
+        "docusaurus" => vec![
+            field("id", "string"),
+            required(field("title", "string")),
+            field("description", "string"),
+            field("slug", "string"),
+            field("sidebar_label", "string"),
+            integer(field("sidebar_position", "number")),
+            field("tags", "string[]"),
+            field("keywords", "string[]"),
+            field("authors", "string[]"),
+            field("date", "date"),
+            field("draft", "boolean"),
+            field("hide_table_of_contents", "boolean"),
+        ],
+        _ => Vec::new(),
+    };
+    fields
+        .into_iter()
+        .enumerate()
+        .map(|(key, field)| FrontmatterField { key, ..field })
+        .collect()
+}
+
+/// Tauri 命令：列出内置的字段预设。
+#[tauri::command]
+pub fn list_schema_presets() -> Vec<SchemaPreset> {
+    GENERATORS
+        .iter()
+        .map(|(id, name, _)| SchemaPreset {
+            id,
+            name,
+            fields: preset_fields(id),
+        })
+        .collect()
+}
+
+/// Tauri 命令：根据工作区根目录中的配置文件识别站点生成器，未识别时返回 None。
+#[tauri::command]
+pub fn detect_site_generator(window: Window) -> Result<Option<DetectedPreset>, String> {
+    let workspace = require_workspace(&window)?;
+    Ok(detect_generator(Path::new(&workspace)))
+}
+
+/// 识别文件存在且确实属于对应的生成器：Gemfile 与 package.json 需要引用 Jekyll 或 Hexo，
+/// `_config.yml` 只在没有 Hexo 识别文件时算作 Jekyll
+fn has_marker(root: &Path, marker: &str) -> bool {
+    let path = root.join(marker);
+    let mentions = |text: &str| fs::read_to_string(&path).is_ok_and(|c| c.contains(text));
+    match marker {
+        "Gemfile" => mentions("jekyll"),
+        "package.json" => mentions("\"hexo\""),
+        "_config.yml" => path.exists() && !HEXO_MARKERS.iter().any(|m| has_marker(root, m)),
+        _ => path.exists(),
+    }
+}
+
+/// 按 `GENERATORS`、`FALLBACK_MARKERS` 的顺序识别 `root` 使用的站点生成器
+fn detect_generator(root: &Path) -> Option<DetectedPreset> {
+    GENERATORS
+        .iter()
+        .map(|(id, _, markers)| (id, markers))
+        .chain(FALLBACK_MARKERS.iter().map(|(id, markers)| (id, markers)))
+        .find_map(|(id, markers)| {
+            let marker = markers.iter().find(|m| has_marker(root, m))?;
+            Some(DetectedPreset {
+                preset: id,
+                marker: marker.to_string(),
+            })
+        })
+}
+
+/// Tauri 命令：将预设的字段定义合并到（或替换）当前字段定义，返回应用后的字段定义。
+#[tauri::command]
+pub fn apply_schema_preset(
+    app: AppHandle,
+    window: Window,
+    preset: String,
+    mode: Option<ImportMode>,
+) -> Result<Vec<FrontmatterField>, String> {
+    let preset_fields = preset_fields(&preset);
+    if preset_fields.is_empty() {
+        return Err(format!("Unknown preset \"{}\"", preset));
+    }
+    let fields = merge_schema(
+        frontmatter_fields(&app, &window)?,
+        preset_fields,
+        mode.unwrap_or_default(),
+    );
+    store_frontmatter_fields(&app, &window, fields.clone())?;
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(files: &[(&str, &str)]) -> Option<(&'static str, String)> {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, content) in files {
+            fs::write(dir.path().join(name), content).unwrap();
+        }
+        detect_generator(dir.path()).map(|d| (d.preset, d.marker))
+    }
+
+    #[test]
+    fn config_yml_is_jekyll_only_without_hexo_markers() {
+        let package = r#"{"dependencies": {"hexo": "^7.0.0"}}"#;
+        assert_eq!(
+            detect(&[("_config.yml", ""), ("package.json", package)]),
+            Some(("hexo", "package.json".to_string()))
+        );
+        assert_eq!(
+            detect(&[("_config.yml", ""), ("_config.landscape.yml", "")]),
+            Some(("hexo", "_config.landscape.yml".to_string()))
+        );
+        assert_eq!(
+            detect(&[("_config.yml", ""), ("package.json", r#"{"name": "blog"}"#)]),
+            Some(("jekyll", "_config.yml".to_string()))
+        );
+    }
+
+    #[test]
+    fn gemfile_counts_only_when_it_mentions_jekyll() {
+        assert_eq!(
+            detect(&[("Gemfile", "gem \"jekyll\"")]),
+            Some(("jekyll", "Gemfile".to_string()))
+        );
+        assert_eq!(detect(&[("Gemfile", "gem \"rails\"")]), None);
+        assert_eq!(
+            detect(&[("Gemfile", "gem \"rails\""), ("_config.yml", "")]),
+            Some(("jekyll", "_config.yml".to_string()))
+        );
+    }
+
+    #[test]
+    fn legacy_hugo_config_is_checked_last() {
+        assert_eq!(
+            detect(&[("config.toml", "")]),
+            Some(("hugo", "config.toml".to_string()))
+        );
+        assert_eq!(
+            detect(&[("config.toml", ""), ("astro.config.mjs", "")]),
+            Some(("astro", "astro.config.mjs".to_string()))
+        );
+    }
+}
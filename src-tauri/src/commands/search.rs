@@ -0,0 +1,397 @@
+use super::frontmatter_query::{parse_query, Query};
+use super::save_frontmatter::extract_frontmatter;
+use super::workspace_config::require_workspace;
+use super::workspace_index::markdown_files;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager, State, Window};
+
+/// 每批发送给前端的结果数量
+const BATCH_SIZE: usize = 50;
+
+/// 上下文片段的最大字符数
+const MAX_SNIPPET_CHARS: usize = 200;
+
+/// 匹配方式
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMode {
+    /// 按字面文本匹配
+    #[default]
+    Plain,
+    /// 只匹配完整单词
+    WholeWord,
+    /// 正则表达式
+    Regex,
+}
+
+/// 搜索范围：正文、frontmatter 或两者
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchTarget {
+    #[default]
+    All,
+    Body,
+    Frontmatter,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchRequest {
+    pub query: String,
+    #[serde(default)]
+    pub mode: SearchMode,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    #[serde(default)]
+    pub target: SearchTarget,
+    /// 只搜索该目录下的文件（绝对路径或相对于工作区的路径）
+    #[serde(default)]
+    pub folder: Option<String>,
+    /// 只搜索 frontmatter 字段满足条件的文件：字段值等于给定值，或为包含该值的数组
+    #[serde(default)]
+    pub frontmatter_filter: HashMap<String, String>,
+    /// 只搜索 frontmatter 满足查询表达式的文件，语法见 `query_frontmatter`
+    #[serde(default)]
+    pub frontmatter_query: Option<String>,
+}
+
+/// 单个匹配结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchMatch {
+    pub path: String,
+    /// 行号（从 1 开始）
+    pub line: usize,
+    /// 列号（从 1 开始，按字符计）
+    pub column: usize,
+    /// 匹配文本的长度（按字符计）
+    pub length: usize,
+    /// 匹配所在行的上下文片段
+    pub snippet: String,
+    /// 匹配开始位置在 `snippet` 中的字符偏移
+    pub snippet_offset: usize,
+    pub in_frontmatter: bool,
+}
+
+#[derive(Clone, Serialize)]
+struct SearchResultsEvent {
+    search_id: String,
+    results: Vec<SearchMatch>,
+}
+
+#[derive(Clone, Serialize)]
+struct SearchFinishedEvent {
+    search_id: String,
+    total_matches: usize,
+    files_searched: usize,
+    cancelled: bool,
+    error: Option<String>,
+}
+
+/// 正在进行的搜索（以 search_id 为键），用于取消
+#[derive(Default)]
+pub struct SearchState {
+    running: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl SearchState {
+    fn start(&self, search_id: &str) -> Arc<AtomicBool> {
+        let flag = Arc::new(AtomicBool::new(false));
+        if let Ok(mut running) = self.running.lock() {
+            // 同一 id 的旧搜索直接取消
+            if let Some(old) = running.insert(search_id.to_string(), flag.clone()) {
+                old.store(true, Ordering::SeqCst);
+            }
+        }
+        flag
+    }
+
+    fn finish(&self, search_id: &str, flag: &Arc<AtomicBool>) {
+        if let Ok(mut running) = self.running.lock() {
+            if running
+                .get(search_id)
+                .is_some_and(|current| Arc::ptr_eq(current, flag))
+            {
+                running.remove(search_id);
+            }
+        }
+    }
+
+    fn cancel(&self, search_id: &str) -> bool {
+        match self.running.lock() {
+            Ok(running) => match running.get(search_id) {
+                Some(flag) => {
+                    flag.store(true, Ordering::SeqCst);
+                    true
+                }
+                None => false,
+            },
+            Err(_) => false,
+        }
+    }
+}
+
+/// 根据匹配方式构建正则表达式
+pub(crate) fn build_search_regex(
+    query: &str,
+    mode: SearchMode,
+    case_sensitive: bool,
+) -> Result<Regex, String> {
+    if query.is_empty() {
+        return Err("Search query is empty".to_string());
+    }
+    let pattern = match mode {
+        SearchMode::Plain => regex::escape(query),
+        SearchMode::WholeWord => {
+            // `\b` 只在单词字符一侧有意义，`C++`、`.NET`、`#tag` 的非单词字符边缘不加边界
+            let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+            let start = if is_word(query.chars().next()) {
+                r"\b"
+            } else {
+                ""
+            };
+            let end = if is_word(query.chars().last()) {
+                r"\b"
+            } else {
+                ""
+            };
+            format!("{}{}{}", start, regex::escape(query), end)
+        }
+        SearchMode::Regex => query.to_string(),
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!case_sensitive)
+        .build()
+        .map_err(|e| format!("Invalid search pattern: {}", e))
+}
+
+/// 返回 frontmatter 所占的行数（包括首尾的 `---` 行），没有 frontmatter 时为 0
+pub(crate) fn frontmatter_line_count(content: &str) -> usize {
+    let mut lines = content.lines();
+    if lines.next().map(|l| l.trim_end()) != Some("---") {
+        return 0;
+    }
+    for (i, line) in lines.enumerate() {
+        if line.trim_end() == "---" {
+            return i + 2;
+        }
+    }
+    0
+}
+
+fn value_matches(value: &serde_yaml::Value, expected: &str) -> bool {
+    match value {
+        serde_yaml::Value::String(s) => s == expected,
+        serde_yaml::Value::Bool(b) => b.to_string() == expected,
+        serde_yaml::Value::Number(n) => n.to_string() == expected,
+        serde_yaml::Value::Sequence(seq) => seq.iter().any(|v| value_matches(v, expected)),
+        serde_yaml::Value::Tagged(tagged) => value_matches(&tagged.value, expected),
+        _ => false,
+    }
+}
+
+pub(crate) fn passes_filter(
+    content: &str,
+    filter: &HashMap<String, String>,
+    query: Option<&Query>,
+) -> bool {
+    if filter.is_empty() && query.is_none() {
+        return true;
+    }
+    let Some(frontmatter) = extract_frontmatter(content) else {
+        return false;
+    };
+    filter.iter().all(|(key, expected)| {
+        frontmatter
+            .get(key)
+            .is_some_and(|value| value_matches(value, expected))
+    }) && query.is_none_or(|query| query.matches(&frontmatter))
+}
+
+/// 解析请求中的 frontmatter 查询表达式（未提供时为 None）
+pub(crate) fn parse_request_query(request: &SearchRequest) -> Result<Option<Query>, String> {
+    request
+        .frontmatter_query
+        .as_deref()
+        .filter(|q| !q.trim().is_empty())
+        .map(parse_query)
+        .transpose()
+}
+
+/// 截取匹配附近的上下文，返回片段以及匹配在片段中的字符偏移
+fn snippet(line: &str, match_start_char: usize) -> (String, usize) {
+    let chars: Vec<char> = line.chars().collect();
+    if chars.len() <= MAX_SNIPPET_CHARS {
+        return (line.to_string(), match_start_char);
+    }
+    let start = match_start_char.saturating_sub(MAX_SNIPPET_CHARS / 4);
+    let end = (start + MAX_SNIPPET_CHARS).min(chars.len());
+    (chars[start..end].iter().collect(), match_start_char - start)
+}
+
+/// 在单个文件内容中查找所有匹配
+pub(crate) fn search_content(
+    path: &Path,
+    content: &str,
+    regex: &Regex,
+    target: SearchTarget,
+) -> Vec<SearchMatch> {
+    let fm_lines = frontmatter_line_count(content);
+    let mut matches = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let in_frontmatter = index < fm_lines;
+        match target {
+            SearchTarget::Body if in_frontmatter => continue,
+            SearchTarget::Frontmatter if !in_frontmatter => break,
+            _ => {}
+        }
+
+        for m in regex.find_iter(line) {
+            if m.as_str().is_empty() {
+                continue;
+            }
+            let column = line[..m.start()].chars().count();
+            let (snippet, snippet_offset) = snippet(line, column);
+            matches.push(SearchMatch {
+                path: path.to_string_lossy().to_string(),
+                line: index + 1,
+                column: column + 1,
+                length: m.as_str().chars().count(),
+                snippet,
+                snippet_offset,
+                in_frontmatter,
+            });
+        }
+    }
+    matches
+}
+
+/// 将相对路径解析为工作区内的绝对路径
+pub(crate) fn resolve_folder(workspace: &str, folder: &str) -> PathBuf {
+    let folder = Path::new(folder);
+    if folder.is_absolute() {
+        folder.to_path_buf()
+    } else {
+        Path::new(workspace).join(folder)
+    }
+}
+
+fn run_search(
+    app: &AppHandle,
+    label: &str,
+    search_id: &str,
+    workspace: &str,
+    request: &SearchRequest,
+    cancelled: &AtomicBool,
+) -> Result<(usize, usize), String> {
+    let regex = build_search_regex(&request.query, request.mode, request.case_sensitive)?;
+    let query = parse_request_query(request)?;
+    let folder = request
+        .folder
+        .as_deref()
+        .map(|folder| resolve_folder(workspace, folder));
+
+    let mut batch: Vec<SearchMatch> = Vec::new();
+    let mut total = 0;
+    let mut files_searched = 0;
+
+    for path in markdown_files(app, workspace)? {
+        if cancelled.load(Ordering::SeqCst) {
+            break;
+        }
+        if folder.as_ref().is_some_and(|f| !path.starts_with(f)) {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        if !passes_filter(&content, &request.frontmatter_filter, query.as_ref()) {
+            continue;
+        }
+
+        files_searched += 1;
+        let found = search_content(&path, &content, &regex, request.target);
+        total += found.len();
+        batch.extend(found);
+
+        if batch.len() >= BATCH_SIZE {
+            emit_batch(app, label, search_id, std::mem::take(&mut batch))?;
+        }
+    }
+
+    if !batch.is_empty() {
+        emit_batch(app, label, search_id, batch)?;
+    }
+    Ok((total, files_searched))
+}
+
+fn emit_batch(
+    app: &AppHandle,
+    label: &str,
+    search_id: &str,
+    results: Vec<SearchMatch>,
+) -> Result<(), String> {
+    app.emit_to(
+        label,
+        "search_results",
+        SearchResultsEvent {
+            search_id: search_id.to_string(),
+            results,
+        },
+    )
+    .map_err(|e| format!("Failed to emit event: {}", e))
+}
+
+/// Tauri 命令：在当前窗口的工作区中搜索 Markdown 文件。
+/// 命令立即返回，结果通过 `search_results` 事件分批发送，
+/// 结束（完成、取消或出错）时发送 `search_finished` 事件。
+#[tauri::command]
+pub fn search_workspace(
+    app: AppHandle,
+    window: Window,
+    state: State<'_, SearchState>,
+    search_id: String,
+    request: SearchRequest,
+) -> Result<(), String> {
+    let workspace = require_workspace(&window)?;
+    // 先校验表达式，便于直接向调用方返回错误
+    build_search_regex(&request.query, request.mode, request.case_sensitive)?;
+    parse_request_query(&request)?;
+
+    let cancelled = state.start(&search_id);
+    let label = window.label().to_string();
+
+    std::thread::spawn(move || {
+        let result = run_search(&app, &label, &search_id, &workspace, &request, &cancelled);
+        let (total_matches, files_searched, error) = match result {
+            Ok((total, files)) => (total, files, None),
+            Err(e) => (0, 0, Some(e)),
+        };
+
+        let finished = SearchFinishedEvent {
+            search_id: search_id.clone(),
+            total_matches,
+            files_searched,
+            cancelled: cancelled.load(Ordering::SeqCst),
+            error,
+        };
+        if let Err(e) = app.emit_to(label.as_str(), "search_finished", finished) {
+            log::warn!("Failed to emit search_finished: {}", e);
+        }
+
+        app.state::<SearchState>().finish(&search_id, &cancelled);
+    });
+
+    Ok(())
+}
+
+/// Tauri 命令：取消正在进行的搜索，返回是否找到该搜索。
+#[tauri::command]
+pub fn cancel_search(state: State<'_, SearchState>, search_id: String) -> bool {
+    state.cancel(&search_id)
+}
@@ -0,0 +1,232 @@
+use super::save_markdown::write_atomic;
+use super::workspace_index::{notify_path_changed, persist, store_suggestions};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, State};
+
+/// 最多保留的撤销记录数量
+const MAX_UNDO_ENTRIES: usize = 20;
+
+/// 一次批量修改中单个文件修改前后的内容
+pub struct FileChange {
+    pub path: PathBuf,
+    pub before: String,
+    pub after: String,
+}
+
+/// 未能修改的文件
+#[derive(Debug, Serialize)]
+pub struct SkippedFile {
+    pub path: String,
+    pub reason: String,
+}
+
+/// 批量写入文件的结果
+#[derive(Serialize)]
+pub struct ChangeSummary {
+    pub files_changed: usize,
+    /// 无法改写或读写失败的文件及原因
+    pub skipped: Vec<SkippedFile>,
+    /// 撤销记录 id，可传给 `undo_change`
+    pub undo_id: Option<u64>,
+    /// 文件已写入（可撤销），但刷新索引或建议失败时的原因
+    pub warning: Option<String>,
+}
+
+/// 一次可撤销的批量修改（例如全局替换、批量修改 frontmatter）
+pub struct UndoEntry {
+    pub id: u64,
+    pub description: String,
+    pub created_at: u64,
+    pub changes: Vec<FileChange>,
+}
+
+/// 撤销记录的摘要，返回给前端
+#[derive(Serialize)]
+pub struct UndoSummary {
+    pub id: u64,
+    pub description: String,
+    pub created_at: u64,
+    pub files: Vec<String>,
+}
+
+/// 撤销的结果
+#[derive(Serialize)]
+pub struct UndoResult {
+    pub restored: Vec<String>,
+    /// 修改后又被编辑过的文件，为避免覆盖新内容而跳过
+    pub skipped: Vec<String>,
+    /// 写回失败的文件及原因；这些文件保留在撤销记录中，可以再次撤销
+    pub failed: Vec<SkippedFile>,
+    /// 仍有文件未恢复时，保留这些文件的撤销记录 id
+    pub undo_id: Option<u64>,
+}
+
+#[derive(Default)]
+pub struct UndoHistory {
+    entries: Mutex<Vec<UndoEntry>>,
+    next_id: Mutex<u64>,
+}
+
+impl UndoHistory {
+    /// 记录一次批量修改，返回撤销记录的 id
+    pub fn record(&self, description: String, changes: Vec<FileChange>) -> Option<u64> {
+        if changes.is_empty() {
+            return None;
+        }
+        let id = {
+            let mut next_id = self.next_id.lock().ok()?;
+            *next_id += 1;
+            *next_id
+        };
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let mut entries = self.entries.lock().ok()?;
+        entries.push(UndoEntry {
+            id,
+            description,
+            created_at,
+            changes,
+        });
+        if entries.len() > MAX_UNDO_ENTRIES {
+            let overflow = entries.len() - MAX_UNDO_ENTRIES;
+            entries.drain(..overflow);
+        }
+        Some(id)
+    }
+
+    /// 将未能恢复的修改放回撤销记录（保持原 id 与在历史中的位置）
+    fn restore(&self, mut entry: UndoEntry) -> Option<u64> {
+        if entry.changes.is_empty() {
+            return None;
+        }
+        let mut entries = self.entries.lock().ok()?;
+        let id = entry.id;
+        let index = entries.partition_point(|e| e.id < id);
+        const PARTIAL: &str = " (partially undone)";
+        if !entry.description.ends_with(PARTIAL) {
+            entry.description.push_str(PARTIAL);
+        }
+        entries.insert(index, entry);
+        Some(id)
+    }
+
+    fn take(&self, id: Option<u64>) -> Result<UndoEntry, String> {
+        let mut entries = self.entries.lock().map_err(|e| e.to_string())?;
+        let index = match id {
+            Some(id) => entries.iter().position(|e| e.id == id),
+            None => entries.len().checked_sub(1),
+        };
+        index
+            .map(|i| entries.remove(i))
+            .ok_or_else(|| "Nothing to undo".to_string())
+    }
+}
+
+/// 将在内存中算好的修改逐个写入磁盘，写入失败的文件记入 `skipped` 后继续。
+/// 已写入的文件先记为一条撤销记录，再刷新索引与建议；刷新失败不影响撤销，作为警告返回。
+pub(crate) fn write_changes(
+    app: &AppHandle,
+    workspace: &str,
+    description: String,
+    changes: Vec<FileChange>,
+    mut skipped: Vec<SkippedFile>,
+) -> ChangeSummary {
+    let mut written = Vec::new();
+    for change in changes {
+        if let Err(e) = write_atomic(&change.path, &change.after) {
+            skipped.push(SkippedFile {
+                path: change.path.to_string_lossy().to_string(),
+                reason: e,
+            });
+            continue;
+        }
+        notify_path_changed(app, &change.path);
+        written.push(change);
+    }
+
+    let files_changed = written.len();
+    let undo_id = app.state::<UndoHistory>().record(description, written);
+    let warning = if files_changed > 0 {
+        persist(app, workspace)
+            .and_then(|_| store_suggestions(app, workspace))
+            .err()
+            .map(|e| format!("Failed to update the workspace index: {}", e))
+    } else {
+        None
+    };
+    ChangeSummary {
+        files_changed,
+        skipped,
+        undo_id,
+        warning,
+    }
+}
+
+/// Tauri 命令：列出可撤销的批量修改（最新的在前）。
+#[tauri::command]
+pub fn list_undo_entries(history: State<'_, UndoHistory>) -> Result<Vec<UndoSummary>, String> {
+    let entries = history.entries.lock().map_err(|e| e.to_string())?;
+    Ok(entries
+        .iter()
+        .rev()
+        .map(|entry| UndoSummary {
+            id: entry.id,
+            description: entry.description.clone(),
+            created_at: entry.created_at,
+            files: entry
+                .changes
+                .iter()
+                .map(|c| c.path.to_string_lossy().to_string())
+                .collect(),
+        })
+        .collect())
+}
+
+/// Tauri 命令：撤销一次批量修改（未指定 id 时撤销最近一次）。
+/// 文件当前内容与修改后的内容不一致时跳过该文件；写回失败的文件不中断撤销，
+/// 并留在撤销记录中以便重试。
+#[tauri::command]
+pub fn undo_change(
+    app: AppHandle,
+    history: State<'_, UndoHistory>,
+    id: Option<u64>,
+) -> Result<UndoResult, String> {
+    let mut entry = history.take(id)?;
+    let mut result = UndoResult {
+        restored: Vec::new(),
+        skipped: Vec::new(),
+        failed: Vec::new(),
+        undo_id: None,
+    };
+
+    let mut remaining = Vec::new();
+    for change in std::mem::take(&mut entry.changes) {
+        let display = change.path.to_string_lossy().to_string();
+        let current = fs::read_to_string(&change.path).unwrap_or_default();
+        if current != change.after {
+            result.skipped.push(display);
+            continue;
+        }
+        if let Err(e) = write_atomic(Path::new(&change.path), &change.before) {
+            result.failed.push(SkippedFile {
+                path: display,
+                reason: e,
+            });
+            remaining.push(change);
+            continue;
+        }
+        notify_path_changed(&app, &change.path);
+        result.restored.push(display);
+    }
+
+    entry.changes = remaining;
+    result.undo_id = history.restore(entry);
+    Ok(result)
+}
@@ -0,0 +1,235 @@
+use super::workspace_config::require_workspace;
+use super::workspace_index::{
+    ensure_index, fuzzy_match, suggestion_values, Frontmatter, RootIndex, WorkspaceIndex,
+};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, Window};
+
+/// 默认返回的最大建议数
+const DEFAULT_LIMIT: usize = 20;
+
+/// 输入内容是值的前缀时的额外得分，使前缀匹配排在模糊匹配之前
+const PREFIX_BONUS: f64 = 100.0;
+/// 使用次数的权重（按对数增长，避免高频值压过其他因素）
+const POPULARITY_WEIGHT: f64 = 10.0;
+/// 最近使用的权重，随时间按半衰期衰减
+const RECENCY_WEIGHT: f64 = 30.0;
+const RECENCY_HALF_LIFE_DAYS: f64 = 30.0;
+/// 与当前文章已有值同时出现的权重。每个已有值按其稀有程度（逆文档频率）加权，
+/// 几乎所有文章都有的值（如 `draft: false`）几乎不起作用
+const CO_OCCURRENCE_WEIGHT: f64 = 40.0;
+
+/// 单条值建议
+#[derive(Serialize)]
+pub struct ValueSuggestion {
+    pub value: String,
+    pub value_type: String,
+    /// 使用该值的文章数
+    pub count: usize,
+    /// 最近一次使用该值的文章的修改时间（Unix 纪元起的秒数）
+    pub last_used: Option<u64>,
+    /// 与当前文章其他字段中的某个已有值同时出现的最多文章数
+    pub co_occurrence: usize,
+    pub score: f64,
+    /// `value` 中匹配字符的位置（按字符计）
+    pub matches: Vec<usize>,
+}
+
+/// 共现得分：对每个同时出现的已有值，按出现次数（对数）乘以该值的逆文档频率后求和
+fn co_occurrence_score(co_occurrence: &[usize], pair_totals: &[usize], total: usize) -> f64 {
+    co_occurrence
+        .iter()
+        .zip(pair_totals)
+        .filter(|(count, _)| **count > 0)
+        .map(|(count, pair_total)| {
+            let idf = (total as f64 / *pair_total as f64).ln();
+            (*count as f64).ln_1p() * idf
+        })
+        .sum()
+}
+
+fn recency_score(last_used: Option<SystemTime>, now: SystemTime) -> f64 {
+    let Some(age) = last_used.and_then(|t| now.duration_since(t).ok()) else {
+        // 没有修改时间或时间在未来时视为刚刚使用
+        return if last_used.is_some() {
+            RECENCY_WEIGHT
+        } else {
+            0.0
+        };
+    };
+    let days = age.as_secs_f64() / 86_400.0;
+    RECENCY_WEIGHT * 0.5f64.powf(days / RECENCY_HALF_LIFE_DAYS)
+}
+
+/// Tauri 命令：为字段 `field`（支持 `author.name` 形式的点分路径）提供值建议。
+/// 按前缀与模糊匹配筛选，并综合使用次数、最近使用时间以及与当前文章
+/// 已有值的共现次数排序；当前文章中该字段已有的值不会出现在结果中。
+/// 每次输入都会调用，在阻塞线程中执行，并使用索引中随文件变化更新的值统计。
+#[tauri::command]
+pub async fn suggest_values(
+    app: AppHandle,
+    window: Window,
+    field: String,
+    prefix: String,
+    current_frontmatter: serde_json::Value,
+    limit: Option<usize>,
+) -> Result<Vec<ValueSuggestion>, String> {
+    let workspace = require_workspace(&window)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        ensure_index(&app, &workspace)?;
+        let current: Frontmatter = serde_json::from_value(current_frontmatter).unwrap_or_default();
+        let limit = limit.unwrap_or(DEFAULT_LIMIT);
+        app.state::<WorkspaceIndex>()
+            .with_root(&workspace, |index| {
+                rank_values(index, &field, &prefix, &current, SystemTime::now(), limit)
+            })
+    })
+    .await
+    .map_err(|e| format!("Background thread failed: {}", e))?
+}
+
+fn rank_values(
+    index: &RootIndex,
+    field: &str,
+    prefix: &str,
+    current: &Frontmatter,
+    now: SystemTime,
+    limit: usize,
+) -> Vec<ValueSuggestion> {
+    let current_values: HashSet<(String, String)> = suggestion_values(current)
+        .into_iter()
+        .map(|(path, (value, _))| (path, value))
+        .collect();
+    // 用于计算共现的已有值（不包括正在填写的字段本身）及含有它们的文章
+    let current_posts: Vec<&HashSet<PathBuf>> = current_values
+        .iter()
+        .filter(|(path, _)| path != field)
+        .filter_map(|(path, value)| index.posts_with(path, value))
+        .collect();
+    let pair_totals: Vec<usize> = current_posts.iter().map(|posts| posts.len()).collect();
+    let total = index.frontmatter_posts();
+
+    let prefix_lower = prefix.trim().to_lowercase();
+    let query: Vec<char> = prefix_lower.chars().collect();
+
+    let mut results: Vec<ValueSuggestion> = index
+        .field_values(field)
+        .into_iter()
+        .filter(|(value, _)| !current_values.contains(&(field.to_string(), value.clone())))
+        .filter_map(|(value, value_type)| {
+            let value_lower = value.to_lowercase();
+            let value_chars: Vec<char> = value_lower.chars().collect();
+            let (match_score, matches) = fuzzy_match(&query, &value_chars, &value)?;
+            let posts = index.posts_with(field, &value)?;
+            let last_used = posts
+                .iter()
+                .filter_map(|path| index.entries.get(path)?.modified)
+                .max();
+            let co_occurrence: Vec<usize> = current_posts
+                .iter()
+                .map(|shared| posts.iter().filter(|path| shared.contains(*path)).count())
+                .collect();
+
+            let mut score = match_score as f64;
+            if !query.is_empty() && value_lower.starts_with(&prefix_lower) {
+                score += PREFIX_BONUS;
+            }
+            score += POPULARITY_WEIGHT * (posts.len() as f64).ln_1p();
+            score += recency_score(last_used, now);
+            score +=
+                CO_OCCURRENCE_WEIGHT * co_occurrence_score(&co_occurrence, &pair_totals, total);
+
+            Some(ValueSuggestion {
+                value,
+                value_type: value_type.to_string(),
+                count: posts.len(),
+                last_used: last_used
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs()),
+                co_occurrence: co_occurrence.iter().copied().max().unwrap_or(0),
+                score,
+                matches,
+            })
+        })
+        .collect();
+
+    results.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| b.count.cmp(&a.count))
+            .then_with(|| a.value.cmp(&b.value))
+    });
+    results.truncate(limit);
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::workspace_index::IndexEntry;
+    use super::*;
+    use std::collections::HashMap;
+
+    fn index(posts: &[&str]) -> RootIndex {
+        let entries: HashMap<PathBuf, IndexEntry> = posts
+            .iter()
+            .enumerate()
+            .map(|(i, yaml)| {
+                let relative = format!("{}.md", i);
+                let entry = IndexEntry::new(
+                    relative.clone(),
+                    Some(serde_yaml::from_str(yaml).unwrap()),
+                    None,
+                    0,
+                );
+                (PathBuf::from("/w").join(relative), entry)
+            })
+            .collect();
+        RootIndex::new(entries)
+    }
+
+    fn ranked(index: &RootIndex, prefix: &str, current: &str) -> Vec<String> {
+        let current: Frontmatter = serde_yaml::from_str(current).unwrap();
+        rank_values(index, "tags", prefix, &current, SystemTime::now(), 10)
+            .into_iter()
+            .map(|s| s.value)
+            .collect()
+    }
+
+    #[test]
+    fn rare_shared_values_outweigh_ubiquitous_ones() {
+        let index = index(&[
+            "draft: false\nseries: rust-book\ntags: [rust, tokio]",
+            "draft: false\ntags: [rust]",
+            "draft: false\ntags: [python]",
+            "draft: false\ntags: [python]",
+            "draft: false\ntags: [python]",
+        ]);
+        // `draft: false` 出现在所有文章中，不影响排序
+        assert_eq!(
+            ranked(&index, "", "draft: false\ntags: [rust]"),
+            ["python", "tokio"]
+        );
+        assert_eq!(
+            ranked(&index, "", "draft: false\nseries: rust-book\ntags: [rust]"),
+            ["tokio", "python"]
+        );
+    }
+
+    #[test]
+    fn filters_by_prefix_and_skips_current_values() {
+        let index = index(&["tags: [rust, ruby, go]", "tags: [rust]"]);
+        assert_eq!(ranked(&index, "ru", "tags: [rust]"), ["ruby"]);
+        assert_eq!(ranked(&index, "ru", "{}"), ["rust", "ruby"]);
+        assert!(ranked(&index, "xyz", "{}").is_empty());
+    }
+
+    #[test]
+    fn co_occurrence_score_ignores_values_in_every_post() {
+        assert_eq!(co_occurrence_score(&[3], &[10], 10), 0.0);
+        assert_eq!(co_occurrence_score(&[0], &[1], 10), 0.0);
+        assert!(co_occurrence_score(&[1], &[1], 10) > co_occurrence_score(&[1], &[5], 10));
+    }
+}
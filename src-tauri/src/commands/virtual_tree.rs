@@ -0,0 +1,323 @@
+use super::frontmatter_dates::{parse_date, ParsedDate};
+use super::get_file_tree::{natural_cmp, TreeNode};
+use super::workspace_index::{markdown_frontmatter, Frontmatter, MarkdownFrontmatter};
+use super::workspaces::window_workspace;
+use chrono::{Datelike, NaiveDate};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Window};
+
+/// 虚拟视图的分组方式
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VirtualView {
+    /// 按 `tags` 分组，一篇文章可以出现在多个分组中
+    Tags,
+    /// 按 `categories` 分组
+    Categories,
+    /// 按 `date` 的年份/月份分组
+    Month,
+    /// 按 `draft` 状态分组
+    Draft,
+}
+
+impl VirtualView {
+    fn name(&self) -> &'static str {
+        match self {
+            VirtualView::Tags => "tags",
+            VirtualView::Categories => "categories",
+            VirtualView::Month => "month",
+            VirtualView::Draft => "draft",
+        }
+    }
+}
+
+/// 没有对应字段的文章所在分组的显示名称；该分组以 `ungrouped` 标记区分，
+/// 不会与同名的真实分组混淆
+const UNGROUPED_NAME: &str = "(none)";
+
+/// 虚拟目录节点的路径前缀，前端可据此区分虚拟节点与真实目录
+const VIRTUAL_SCHEME: &str = "virtual://";
+
+fn file_node(path: &Path) -> TreeNode {
+    TreeNode {
+        name: path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        path: path.to_string_lossy().to_string(),
+        is_dir: false,
+        children: None,
+        size: fs::metadata(path).ok().map(|m| m.len()),
+        ungrouped: false,
+    }
+}
+
+fn group_node(name: String, path: String, mut children: Vec<TreeNode>) -> TreeNode {
+    children.sort_by(|a, b| natural_cmp(&a.name, &b.name));
+    TreeNode {
+        name,
+        path,
+        is_dir: true,
+        children: Some(children),
+        size: None,
+        ungrouped: false,
+    }
+}
+
+/// 收纳没有对应字段的文章的分组，路径为 `virtual://<视图>?ungrouped`
+fn ungrouped_node(view_name: &str, children: Vec<TreeNode>) -> TreeNode {
+    let path = format!("{}{}?ungrouped", VIRTUAL_SCHEME, view_name);
+    TreeNode {
+        ungrouped: true,
+        ..group_node(UNGROUPED_NAME.to_string(), path, children)
+    }
+}
+
+/// 取出字段中的所有字符串值（支持单个字符串与字符串数组）
+fn string_values(value: Option<&serde_yaml::Value>) -> Vec<String> {
+    match value {
+        Some(serde_yaml::Value::String(s)) if !s.trim().is_empty() => vec![s.trim().to_string()],
+        Some(serde_yaml::Value::Sequence(seq)) => seq
+            .iter()
+            .filter_map(|item| item.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// 从 `date` 字段中解析出 (年, 月)；带时区的值取其自身时区中的日期
+fn year_month(value: Option<&serde_yaml::Value>) -> Option<(i32, u32)> {
+    let s = match value? {
+        serde_yaml::Value::String(s) => s,
+        serde_yaml::Value::Tagged(tagged) => tagged.value.as_str()?,
+        _ => return None,
+    };
+    let date: NaiveDate = match parse_date(s)? {
+        ParsedDate::Offset(dt) => dt.date_naive(),
+        ParsedDate::Naive(dt) => dt.date(),
+        ParsedDate::Date(date) => date,
+        ParsedDate::Time(_) => return None,
+    };
+    Some((date.year(), date.month()))
+}
+
+fn is_draft(value: Option<&serde_yaml::Value>) -> bool {
+    match value {
+        Some(serde_yaml::Value::Bool(b)) => *b,
+        Some(serde_yaml::Value::String(s)) => s.trim().eq_ignore_ascii_case("true"),
+        _ => false,
+    }
+}
+
+/// 按 `group_keys` 返回的分组名分组，生成 分组 -> 文件 的两层结构；
+/// 同一篇文章在每个分组中只出现一次
+fn build_flat_groups(
+    view_name: &str,
+    files: &[MarkdownFrontmatter],
+    group_keys: impl Fn(Option<&Frontmatter>) -> Vec<String>,
+) -> Vec<TreeNode> {
+    let mut groups: BTreeMap<String, Vec<TreeNode>> = BTreeMap::new();
+    let mut ungrouped: Vec<TreeNode> = Vec::new();
+
+    for (path, frontmatter) in files {
+        let mut keys = group_keys(frontmatter.as_deref());
+        keys.sort();
+        keys.dedup();
+        if keys.is_empty() {
+            ungrouped.push(file_node(path));
+        }
+        for key in keys {
+            groups.entry(key).or_default().push(file_node(path));
+        }
+    }
+
+    let mut nodes: Vec<TreeNode> = groups
+        .into_iter()
+        .map(|(key, children)| {
+            let path = format!("{}{}/{}", VIRTUAL_SCHEME, view_name, key);
+            group_node(key, path, children)
+        })
+        .collect();
+    nodes.sort_by(|a, b| natural_cmp(&a.name, &b.name));
+    // “无分组”始终放在最后
+    if !ungrouped.is_empty() {
+        nodes.push(ungrouped_node(view_name, ungrouped));
+    }
+    nodes
+}
+
+/// 按日期生成 年 -> 月 -> 文件 的三层结构，最新的年份/月份排在最前
+fn build_month_groups(files: &[MarkdownFrontmatter]) -> Vec<TreeNode> {
+    let mut years: BTreeMap<i32, BTreeMap<u32, Vec<TreeNode>>> = BTreeMap::new();
+    let mut undated: Vec<TreeNode> = Vec::new();
+
+    for (path, frontmatter) in files {
+        match year_month(frontmatter.as_ref().and_then(|f| f.get("date"))) {
+            Some((year, month)) => years
+                .entry(year)
+                .or_default()
+                .entry(month)
+                .or_default()
+                .push(file_node(path)),
+            None => undated.push(file_node(path)),
+        }
+    }
+
+    let mut nodes: Vec<TreeNode> = years
+        .into_iter()
+        .rev()
+        .map(|(year, months)| {
+            let months: Vec<TreeNode> = months
+                .into_iter()
+                .rev()
+                .map(|(month, children)| {
+                    let path = format!("{}month/{}/{:02}", VIRTUAL_SCHEME, year, month);
+                    group_node(format!("{}-{:02}", year, month), path, children)
+                })
+                .collect();
+            TreeNode {
+                path: format!("{}month/{}", VIRTUAL_SCHEME, year),
+                name: year.to_string(),
+                is_dir: true,
+                children: Some(months),
+                size: None,
+                ungrouped: false,
+            }
+        })
+        .collect();
+
+    if !undated.is_empty() {
+        nodes.push(ungrouped_node("month", undated));
+    }
+    nodes
+}
+
+/// Tauri 命令：根据 frontmatter 生成工作区的虚拟文件树。
+/// 未指定 `path` 时使用当前窗口的工作区路径。返回的结构与 `get_file_tree` 相同，
+/// 虚拟目录的 `path` 以 `virtual://` 开头，文件节点的 `path` 为真实路径。
+#[tauri::command]
+pub async fn get_virtual_tree(
+    app: AppHandle,
+    window: Window,
+    view: VirtualView,
+    path: Option<String>,
+) -> Result<TreeNode, String> {
+    let root = match path {
+        Some(path) => path,
+        None => window_workspace(&window)?.ok_or_else(|| "未选择工作目录".to_string())?,
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let root_path = PathBuf::from(&root);
+        if !root_path.is_dir() {
+            return Err(format!("路径不是目录: {}", root_path.display()));
+        }
+
+        let files = markdown_frontmatter(&app, &root)?;
+        let name = view.name();
+        let children = match view {
+            VirtualView::Tags | VirtualView::Categories => build_flat_groups(name, &files, |fm| {
+                string_values(fm.and_then(|f| f.get(name)))
+            }),
+            VirtualView::Draft => build_flat_groups(name, &files, |fm| {
+                let draft = is_draft(fm.and_then(|f| f.get("draft")));
+                vec![if draft { "draft" } else { "published" }.to_string()]
+            }),
+            VirtualView::Month => build_month_groups(&files),
+        };
+
+        Ok(TreeNode {
+            name: view.name().to_string(),
+            path: format!("{}{}", VIRTUAL_SCHEME, view.name()),
+            is_dir: true,
+            children: Some(children),
+            size: None,
+            ungrouped: false,
+        })
+    })
+    .await
+    .map_err(|e| format!("后台线程执行失败: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn post(path: &str, yaml: &str) -> MarkdownFrontmatter {
+        (
+            PathBuf::from(path),
+            Some(Arc::new(serde_yaml::from_str(yaml).unwrap())),
+        )
+    }
+
+    fn date(s: &str) -> Option<(i32, u32)> {
+        year_month(Some(&serde_yaml::Value::String(s.to_string())))
+    }
+
+    #[test]
+    fn year_month_parses_common_date_forms() {
+        assert_eq!(date("2024-01-05"), Some((2024, 1)));
+        assert_eq!(date("2024/1/5"), Some((2024, 1)));
+        assert_eq!(date("2024-11-05 10:00:00"), Some((2024, 11)));
+        assert_eq!(date("2024-12-31T23:00:00-05:00"), Some((2024, 12)));
+        assert_eq!(date("2024-13-01"), None);
+        assert_eq!(date("10:00"), None);
+        assert_eq!(date("someday"), None);
+    }
+
+    #[test]
+    fn flat_groups_dedupe_and_keep_ungrouped_separate() {
+        let files = vec![
+            post("/w/a.md", "tags: [rust, rust, '(none)']"),
+            post("/w/b.md", "title: b"),
+        ];
+        let nodes = build_flat_groups("tags", &files, |fm| {
+            string_values(fm.and_then(|f| f.get("tags")))
+        });
+
+        let summary: Vec<(&str, bool, usize)> = nodes
+            .iter()
+            .map(|n| {
+                let count = n.children.as_ref().map_or(0, |c| c.len());
+                (n.name.as_str(), n.ungrouped, count)
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("(none)", false, 1),
+                ("rust", false, 1),
+                ("(none)", true, 1)
+            ]
+        );
+        assert_eq!(nodes[2].path, "virtual://tags?ungrouped");
+        assert_ne!(nodes[0].path, nodes[2].path);
+    }
+
+    #[test]
+    fn month_groups_sort_newest_first() {
+        let files = vec![
+            post("/w/a.md", "date: 2023-05-01"),
+            post("/w/b.md", "date: 2024/1/5"),
+            post("/w/c.md", "date: 2024-03-01"),
+            post("/w/d.md", "date: 2024-13-01"),
+        ];
+        let nodes = build_month_groups(&files);
+        let names: Vec<&str> = nodes.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, ["2024", "2023", "(none)"]);
+        let months: Vec<&str> = nodes[0]
+            .children
+            .iter()
+            .flatten()
+            .map(|n| n.name.as_str())
+            .collect();
+        assert_eq!(months, ["2024-03", "2024-01"]);
+        assert!(nodes[2].ungrouped);
+    }
+}
@@ -0,0 +1,388 @@
+use super::get_file_tree::TreeSortOrder;
+use super::save_frontmatter::FrontmatterField;
+use super::schema_files::validate_schema;
+use super::workspaces::window_workspace;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Window};
+use tauri_plugin_store::StoreExt;
+
+/// 项目内共享配置所在的目录与文件名
+const SHARED_CONFIG_DIR: &str = ".rsoul";
+const SHARED_CONFIG_FILE: &str = "config.toml";
+
+/// 工作区级别的设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceSettings {
+    /// 文件树排序方式
+    #[serde(default)]
+    pub tree_sort: TreeSortOrder,
+}
+
+/// 只用于部分目录的字段定义
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct FolderSchema {
+    /// 相对工作区根目录的 glob，如 `docs/**`；不含通配符时视为目录
+    pub pattern: String,
+    #[serde(default)]
+    pub fields: Vec<FrontmatterField>,
+}
+
+/// 单个工作区的配置：设置与 frontmatter 字段定义
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceConfig {
+    #[serde(default)]
+    pub settings: WorkspaceSettings,
+    #[serde(default)]
+    pub frontmatter_fields: Vec<FrontmatterField>,
+    /// 按目录指定的字段定义，优先于 `frontmatter_fields`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub folder_schemas: Vec<FolderSchema>,
+}
+
+/// 将目录 glob 编译为匹配模式，返回模式与其具体程度（不含通配符的路径段数）
+fn folder_pattern(pattern: &str) -> Result<(glob::Pattern, usize), String> {
+    let trimmed = pattern
+        .trim()
+        .trim_start_matches("./")
+        .trim_matches('/')
+        .replace('\\', "/");
+    if trimmed.is_empty() {
+        return Err("Folder pattern must not be empty".to_string());
+    }
+    let has_wildcard = |s: &str| s.contains(['*', '?', '[']);
+    let glob = if has_wildcard(&trimmed) {
+        trimmed
+    } else {
+        format!("{}/**", trimmed)
+    };
+    let specificity = glob.split('/').filter(|s| !has_wildcard(s)).count();
+    glob::Pattern::new(&glob)
+        .map(|p| (p, specificity))
+        .map_err(|e| format!("Invalid folder pattern \"{}\": {}", pattern, e))
+}
+
+impl WorkspaceConfig {
+    /// 文件适用的字段定义：匹配的目录 glob 中最具体的一个（同样具体时取较长的模式，
+    /// 仍相同时取后定义的），没有匹配时使用工作区的字段定义
+    pub(crate) fn schema_for(&self, root: &str, path: &Path) -> &[FrontmatterField] {
+        let Ok(relative) = path.strip_prefix(root) else {
+            return &self.frontmatter_fields;
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        let options = glob::MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+        self.folder_schemas
+            .iter()
+            .filter_map(|schema| {
+                let (pattern, specificity) = folder_pattern(&schema.pattern).ok()?;
+                pattern.matches_with(&relative, options).then_some((
+                    specificity,
+                    pattern.as_str().len(),
+                    schema,
+                ))
+            })
+            .max_by_key(|(specificity, len, _)| (*specificity, *len))
+            .map(|(_, _, schema)| schema.fields.as_slice())
+            .unwrap_or(&self.frontmatter_fields)
+    }
+}
+
+/// 返回给前端的配置以及其来源
+#[derive(Serialize)]
+pub struct WorkspaceConfigInfo {
+    pub workspace: String,
+    pub config: WorkspaceConfig,
+    /// 配置是否来自项目中的 `.rsoul/config.toml`
+    pub shared: bool,
+    /// 项目中存在 `.rsoul/config.toml`（关闭共享后文件仍保留，但不再读取）
+    pub shared_file_exists: bool,
+    pub config_path: String,
+}
+
+/// 项目内共享配置文件的路径：`<root>/.rsoul/config.toml`
+pub(crate) fn shared_config_path(root: &str) -> PathBuf {
+    Path::new(root)
+        .join(SHARED_CONFIG_DIR)
+        .join(SHARED_CONFIG_FILE)
+}
+
+fn store_key(root: &str) -> String {
+    format!("workspace:{}", root)
+}
+
+/// 关闭共享后记录在应用存储中的标记：项目文件仍保留，但此工作区不再读取它
+fn ignore_shared_key(root: &str) -> String {
+    format!("workspace-ignore-shared:{}", root)
+}
+
+/// 工作区是否读取项目中的 `.rsoul/config.toml`
+fn uses_shared_config(app: &AppHandle, root: &str) -> Result<bool, String> {
+    if !shared_config_path(root).is_file() {
+        return Ok(false);
+    }
+    let store = app
+        .store(".workspaces.dat")
+        .map_err(|e| format!("Failed to open workspaces store: {}", e))?;
+    Ok(store.get(ignore_shared_key(root)) != Some(serde_json::Value::Bool(true)))
+}
+
+fn read_shared_config(path: &Path) -> Result<WorkspaceConfig, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    toml::from_str(&content).map_err(|e| format!("Invalid {}: {}", path.display(), e))
+}
+
+fn write_shared_config(path: &Path, config: &WorkspaceConfig) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let content =
+        toml::to_string_pretty(config).map_err(|e| format!("TOML serialization error: {}", e))?;
+    fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// 读取工作区配置。使用共享配置时以 `.rsoul/config.toml` 为准，否则读取应用存储；
+/// 工作区尚未保存过字段定义时，沿用旧版的全局字段定义。
+///
+/// 配置文件无效时只记录警告并退回应用存储中的配置（或默认配置），
+/// 使文件树、保存文章等功能不受影响；需要报告错误的配置命令使用 [`read_workspace_config`]。
+pub(crate) fn load_workspace_config(
+    app: &AppHandle,
+    root: &str,
+) -> Result<WorkspaceConfig, String> {
+    if uses_shared_config(app, root)? {
+        match read_shared_config(&shared_config_path(root)) {
+            Ok(config) => return Ok(config),
+            Err(e) => log::warn!("{}; falling back to the stored config", e),
+        }
+    }
+    load_stored_config(app, root)
+}
+
+/// 读取工作区配置，共享配置文件无效时返回错误。
+pub(crate) fn read_workspace_config(
+    app: &AppHandle,
+    root: &str,
+) -> Result<WorkspaceConfig, String> {
+    if uses_shared_config(app, root)? {
+        return read_shared_config(&shared_config_path(root));
+    }
+    load_stored_config(app, root)
+}
+
+/// 应用存储中的工作区配置，无效时记录警告并使用默认配置
+fn load_stored_config(app: &AppHandle, root: &str) -> Result<WorkspaceConfig, String> {
+    let store = app
+        .store(".workspaces.dat")
+        .map_err(|e| format!("Failed to open workspaces store: {}", e))?;
+    if let Some(value) = store.get(store_key(root)) {
+        match serde_json::from_value(value) {
+            Ok(config) => return Ok(config),
+            Err(e) => {
+                log::warn!("Invalid workspace config for {}: {}", root, e);
+                return Ok(WorkspaceConfig::default());
+            }
+        }
+    }
+
+    let frontmatter_store = app
+        .store(".frontmatter.dat")
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+    let frontmatter_fields: Vec<FrontmatterField> = serde_json::from_value(
+        frontmatter_store
+            .get("frontmatter_fields")
+            .unwrap_or_default(),
+    )
+    .unwrap_or_default();
+
+    Ok(WorkspaceConfig {
+        frontmatter_fields,
+        ..Default::default()
+    })
+}
+
+/// 保存工作区配置，写回配置当前所在的位置（项目文件或应用存储）。
+/// 共享配置文件无效时返回错误，避免用退回的配置覆盖其中的内容。
+pub(crate) fn save_workspace_config(
+    app: &AppHandle,
+    root: &str,
+    config: &WorkspaceConfig,
+) -> Result<(), String> {
+    if uses_shared_config(app, root)? {
+        let shared = shared_config_path(root);
+        read_shared_config(&shared)?;
+        return write_shared_config(&shared, config);
+    }
+    store_config(app, root, config)
+}
+
+fn store_config(app: &AppHandle, root: &str, config: &WorkspaceConfig) -> Result<(), String> {
+    let store = app
+        .store(".workspaces.dat")
+        .map_err(|e| format!("Failed to open workspaces store: {}", e))?;
+    store.set(
+        store_key(root),
+        serde_json::to_value(config).map_err(|e| format!("Serialization error: {}", e))?,
+    );
+    store
+        .save()
+        .map_err(|e| format!("Failed to save workspaces store: {}", e))
+}
+
+/// 当前窗口的工作区路径，未选择工作区时返回错误。
+pub(crate) fn require_workspace(window: &Window) -> Result<String, String> {
+    window_workspace(window)?.ok_or_else(|| "No workspace selected".to_string())
+}
+
+/// Tauri 命令：获取当前窗口工作区的配置及其来源。
+#[tauri::command]
+pub fn get_workspace_config(app: AppHandle, window: Window) -> Result<WorkspaceConfigInfo, String> {
+    let workspace = require_workspace(&window)?;
+    let config = read_workspace_config(&app, &workspace)?;
+    let path = shared_config_path(&workspace);
+
+    Ok(WorkspaceConfigInfo {
+        shared: uses_shared_config(&app, &workspace)?,
+        shared_file_exists: path.is_file(),
+        config_path: path.to_string_lossy().to_string(),
+        workspace,
+        config,
+    })
+}
+
+/// Tauri 命令：保存当前窗口工作区的设置（保留字段定义不变）。
+#[tauri::command]
+pub fn save_workspace_settings(
+    app: AppHandle,
+    window: Window,
+    settings: WorkspaceSettings,
+) -> Result<(), String> {
+    let workspace = require_workspace(&window)?;
+    let mut config = read_workspace_config(&app, &workspace)?;
+    config.settings = settings;
+    save_workspace_config(&app, &workspace, &config)
+}
+
+/// Tauri 命令：保存当前窗口工作区按目录指定的字段定义，保存前检查模式与字段定义。
+#[tauri::command]
+pub fn save_folder_schemas(
+    app: AppHandle,
+    window: Window,
+    folder_schemas: Vec<FolderSchema>,
+) -> Result<(), String> {
+    let workspace = require_workspace(&window)?;
+    for schema in &folder_schemas {
+        folder_pattern(&schema.pattern)?;
+        validate_schema(&schema.fields)
+            .map_err(|e| format!("Schema for \"{}\": {}", schema.pattern, e))?;
+    }
+    let mut config = read_workspace_config(&app, &workspace)?;
+    config.folder_schemas = folder_schemas;
+    save_workspace_config(&app, &workspace, &config)
+}
+
+/// Tauri 命令：切换工作区配置是否保存在项目的 `.rsoul/config.toml` 中。
+/// 开启时读取该文件（文件不存在时写入当前配置）；关闭时将其内容复制到应用存储，
+/// 此后不再读取该文件。项目中的文件可能由其他人共享，不会被删除。
+#[tauri::command]
+pub fn set_workspace_config_shared(
+    app: AppHandle,
+    window: Window,
+    shared: bool,
+) -> Result<(), String> {
+    let workspace = require_workspace(&window)?;
+    let config = read_workspace_config(&app, &workspace)?;
+    let path = shared_config_path(&workspace);
+    let store = app
+        .store(".workspaces.dat")
+        .map_err(|e| format!("Failed to open workspaces store: {}", e))?;
+
+    if shared {
+        if !path.is_file() {
+            write_shared_config(&path, &config)?;
+        }
+        store.delete(ignore_shared_key(&workspace));
+    } else {
+        store_config(&app, &workspace, &config)?;
+        store.set(ignore_shared_key(&workspace), serde_json::Value::Bool(true));
+    }
+    store
+        .save()
+        .map_err(|e| format!("Failed to save workspaces store: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(title: &str) -> Vec<FrontmatterField> {
+        vec![FrontmatterField {
+            title: title.to_string(),
+            field_type: "string".to_string(),
+            ..Default::default()
+        }]
+    }
+
+    fn schema_title(config: &WorkspaceConfig, path: &str) -> String {
+        config.schema_for("/w", Path::new(path))[0].title.clone()
+    }
+
+    #[test]
+    fn schema_for_prefers_the_most_specific_folder() {
+        let config = WorkspaceConfig {
+            frontmatter_fields: fields("root"),
+            folder_schemas: vec![
+                FolderSchema {
+                    pattern: "docs".to_string(),
+                    fields: fields("docs"),
+                },
+                FolderSchema {
+                    pattern: "./docs/api/".to_string(),
+                    fields: fields("api"),
+                },
+                FolderSchema {
+                    pattern: "*/drafts/*.md".to_string(),
+                    fields: fields("drafts"),
+                },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(schema_title(&config, "/w/docs/intro.md"), "docs");
+        assert_eq!(schema_title(&config, "/w/docs/api/v1/get.md"), "api");
+        assert_eq!(schema_title(&config, "/w/blog/drafts/a.md"), "drafts");
+        assert_eq!(schema_title(&config, "/w/blog/drafts/old/a.md"), "root");
+        assert_eq!(schema_title(&config, "/w/post.md"), "root");
+        assert_eq!(schema_title(&config, "/elsewhere/docs/a.md"), "root");
+    }
+
+    #[test]
+    fn folder_pattern_rejects_empty_and_invalid_patterns() {
+        assert!(folder_pattern(" / ").is_err());
+        assert!(folder_pattern("docs/[").is_err());
+        assert_eq!(folder_pattern("docs/api").unwrap().1, 2);
+    }
+
+    #[test]
+    fn shared_config_round_trips_and_reports_invalid_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_string_lossy().to_string();
+        let path = shared_config_path(&root);
+
+        let config = WorkspaceConfig {
+            frontmatter_fields: fields("title"),
+            ..Default::default()
+        };
+        write_shared_config(&path, &config).unwrap();
+        let read = read_shared_config(&path).unwrap();
+        assert_eq!(read.frontmatter_fields[0].title, "title");
+
+        fs::write(&path, "frontmatter_fields = [").unwrap();
+        let error = read_shared_config(&path).err().unwrap();
+        assert!(error.starts_with("Invalid "), "{}", error);
+    }
+}
@@ -0,0 +1,1185 @@
+use super::save_frontmatter::{
+    extract_frontmatter, suggestions_key, FrontmatterSuggestion, FrontmatterSuggestions,
+};
+use super::workspace_config::require_workspace;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, Window};
+use tauri_plugin_store::StoreExt;
+use walkdir::WalkDir;
+
+/// 没有文件监听（监听启动失败）时，索引超过该时间后下一次查询会在后台重新扫描
+const STALE_AFTER: Duration = Duration::from_secs(30);
+
+/// 默认返回的最大结果数
+const DEFAULT_LIMIT: usize = 50;
+
+/// 扫描时跳过的目录
+const IGNORED_DIRS: &[&str] = &["node_modules", "target", "dist"];
+
+/// 持久化 frontmatter 索引的存储文件。v2 起 frontmatter 以 YAML 文本保存，
+/// 旧文件中经 JSON 转换丢失了 YAML 标签，不再读取
+const INDEX_STORE: &str = ".frontmatter-index.v2.dat";
+
+/// 单篇保存或文件监听触发持久化前的等待时间，期间的多次修改合并为一次写入
+const PERSIST_DELAY: Duration = Duration::from_secs(2);
+
+/// 解析后的 frontmatter（顶层键 -> 值）
+pub(crate) type Frontmatter = HashMap<String, serde_yaml::Value>;
+
+/// Markdown 文件路径及其 frontmatter（没有有效 frontmatter 时为 None）
+pub(crate) type MarkdownFrontmatter = (PathBuf, Option<Arc<Frontmatter>>);
+
+/// 索引中的单个文件
+#[derive(Clone)]
+pub struct IndexEntry {
+    /// 相对于工作区根目录的路径，统一使用 `/` 分隔
+    pub relative_path: String,
+    /// Markdown 文件 frontmatter 中的 `title`
+    pub title: Option<String>,
+    /// Markdown 文件解析后的 frontmatter
+    pub frontmatter: Option<Arc<Frontmatter>>,
+    pub modified: Option<SystemTime>,
+    pub size: u64,
+    relative_lower: Vec<char>,
+    title_lower: Vec<char>,
+}
+
+impl IndexEntry {
+    pub(crate) fn new(
+        relative_path: String,
+        frontmatter: Option<Frontmatter>,
+        modified: Option<SystemTime>,
+        size: u64,
+    ) -> Self {
+        let title = frontmatter
+            .as_ref()
+            .and_then(|fm| fm.get("title"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let relative_lower = relative_path.to_lowercase().chars().collect();
+        let title_lower = title
+            .as_deref()
+            .map(|t| t.to_lowercase().chars().collect())
+            .unwrap_or_default();
+        IndexEntry {
+            relative_path,
+            title,
+            frontmatter: frontmatter.map(Arc::new),
+            modified,
+            size,
+            relative_lower,
+            title_lower,
+        }
+    }
+}
+
+/// 持久化的索引条目：以路径 + mtime/大小判断文件是否需要重新解析
+#[derive(Serialize, Deserialize)]
+struct PersistedEntry {
+    /// 修改时间（Unix 纪元起的秒数与纳秒）
+    modified: Option<(u64, u32)>,
+    size: u64,
+    /// frontmatter 的 YAML 文本，保留 `!tag` 等无法用 JSON 表示的值
+    frontmatter: Option<String>,
+}
+
+/// 单个工作区的文件索引
+pub struct RootIndex {
+    pub entries: HashMap<PathBuf, IndexEntry>,
+    /// 字段 -> (值, 类型) -> 出现次数，随条目增删就地更新
+    suggestion_counts: HashMap<String, HashMap<(String, &'static str), usize>>,
+    /// 字段 -> 值 -> 含有该值的文章，随条目增删就地更新，用于值建议的排序
+    value_posts: HashMap<String, HashMap<String, HashSet<PathBuf>>>,
+    /// 有 frontmatter 的文章数
+    frontmatter_posts: usize,
+    refreshed_at: Instant,
+    refreshing: bool,
+}
+
+impl RootIndex {
+    pub(crate) fn new(entries: HashMap<PathBuf, IndexEntry>) -> Self {
+        let mut index = RootIndex {
+            entries: HashMap::new(),
+            suggestion_counts: HashMap::new(),
+            value_posts: HashMap::new(),
+            frontmatter_posts: 0,
+            refreshed_at: Instant::now(),
+            refreshing: false,
+        };
+        for (path, entry) in entries {
+            index.insert(path, entry);
+        }
+        index
+    }
+
+    fn insert(&mut self, path: PathBuf, entry: IndexEntry) {
+        if let Some(old) = self.entries.remove(&path) {
+            self.count(&path, &old, false);
+        }
+        self.count(&path, &entry, true);
+        self.entries.insert(path, entry);
+    }
+
+    fn remove_under(&mut self, path: &Path) -> HashMap<PathBuf, IndexEntry> {
+        let keys: Vec<PathBuf> = self
+            .entries
+            .keys()
+            .filter(|p| p.starts_with(path))
+            .cloned()
+            .collect();
+        let mut removed = HashMap::new();
+        for key in keys {
+            if let Some(entry) = self.entries.remove(&key) {
+                self.count(&key, &entry, false);
+                removed.insert(key, entry);
+            }
+        }
+        removed
+    }
+
+    /// 将条目的 frontmatter 值计入（或移出）建议计数
+    fn count(&mut self, path: &Path, entry: &IndexEntry, add: bool) {
+        let Some(frontmatter) = entry.frontmatter.as_deref() else {
+            return;
+        };
+        if add {
+            self.frontmatter_posts += 1;
+        } else {
+            self.frontmatter_posts -= 1;
+        }
+        for (field, value) in suggestion_values(frontmatter) {
+            let posts = self.value_posts.entry(field.clone()).or_default();
+            if add {
+                posts
+                    .entry(value.0.clone())
+                    .or_default()
+                    .insert(path.to_path_buf());
+            } else if let Some(paths) = posts.get_mut(&value.0) {
+                paths.remove(path);
+                if paths.is_empty() {
+                    posts.remove(&value.0);
+                }
+            }
+            if posts.is_empty() {
+                self.value_posts.remove(&field);
+            }
+
+            let values = self.suggestion_counts.entry(field.clone()).or_default();
+            if add {
+                *values.entry(value).or_insert(0) += 1;
+            } else if let Some(count) = values.get_mut(&value) {
+                *count -= 1;
+                if *count == 0 {
+                    values.remove(&value);
+                }
+                if values.is_empty() {
+                    self.suggestion_counts.remove(&field);
+                }
+            }
+        }
+    }
+
+    /// 字段中出现过的所有 (值, 类型)
+    pub(crate) fn field_values(&self, field: &str) -> Vec<(String, &'static str)> {
+        self.suggestion_counts
+            .get(field)
+            .map(|values| values.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// 字段中含有 `value` 的文章
+    pub(crate) fn posts_with(&self, field: &str, value: &str) -> Option<&HashSet<PathBuf>> {
+        self.value_posts.get(field)?.get(value)
+    }
+
+    /// 有 frontmatter 的文章数
+    pub(crate) fn frontmatter_posts(&self) -> usize {
+        self.frontmatter_posts
+    }
+
+    fn suggestions(&self) -> FrontmatterSuggestions {
+        let field_suggestions = self
+            .suggestion_counts
+            .iter()
+            .map(|(field, values)| {
+                let mut sorted: Vec<FrontmatterSuggestion> = values
+                    .iter()
+                    .map(|((value, value_type), count)| FrontmatterSuggestion {
+                        value: value.clone(),
+                        value_type: value_type.to_string(),
+                        count: *count,
+                    })
+                    .collect();
+                sorted.sort_by(|a, b| {
+                    b.count
+                        .cmp(&a.count)
+                        .then_with(|| a.value.cmp(&b.value))
+                        .then_with(|| a.value_type.cmp(&b.value_type))
+                });
+                (field.clone(), sorted)
+            })
+            .collect();
+        FrontmatterSuggestions { field_suggestions }
+    }
+}
+
+/// 标量值的文本形式及其类型（类型名与字段定义的 `field_type` 一致）
+pub(crate) fn scalar_value(value: &serde_yaml::Value) -> Option<(String, &'static str)> {
+    match value {
+        serde_yaml::Value::String(s) => Some((s.clone(), string_type(s))),
+        serde_yaml::Value::Number(n) => Some((n.to_string(), "number")),
+        serde_yaml::Value::Bool(b) => Some((b.to_string(), "boolean")),
+        serde_yaml::Value::Tagged(tagged) => scalar_value(&tagged.value),
+        _ => None,
+    }
+}
+
+/// 根据字符串的形状识别日期与时间（YAML 解析后它们仍是字符串）
+fn string_type(s: &str) -> &'static str {
+    let bytes = s.as_bytes();
+    let digits = |range: std::ops::Range<usize>| {
+        bytes.len() >= range.end && bytes[range].iter().all(u8::is_ascii_digit)
+    };
+    let is_date = digits(0..4)
+        && bytes.get(4) == Some(&b'-')
+        && digits(5..7)
+        && bytes.get(7) == Some(&b'-')
+        && digits(8..10);
+    if is_date {
+        return match bytes.get(10) {
+            None => "date",
+            Some(b'T' | b't' | b' ') if digits(11..13) && bytes.get(13) == Some(&b':') => {
+                "dateandtime"
+            }
+            Some(_) => "string",
+        };
+    }
+    let is_time = digits(0..2) && bytes.get(2) == Some(&b':') && digits(3..5);
+    let rest_ok = match bytes.get(5) {
+        None => true,
+        Some(b':') => digits(6..8) && bytes.len() == 8,
+        Some(_) => false,
+    };
+    if is_time && rest_ok {
+        "time"
+    } else {
+        "string"
+    }
+}
+
+/// frontmatter 中参与建议统计的 (字段, (值, 类型))。
+/// 嵌套映射以点分路径展开（如 `author.name`），序列中的标量计入所在字段，
+/// 序列中的映射按同样的路径继续展开（如 `authors.name`）。
+pub(crate) fn suggestion_values(
+    frontmatter: &Frontmatter,
+) -> Vec<(String, (String, &'static str))> {
+    let mut values = Vec::new();
+    for (key, value) in frontmatter {
+        collect_values(key, value, &mut values);
+    }
+    values
+}
+
+fn collect_values(
+    path: &str,
+    value: &serde_yaml::Value,
+    values: &mut Vec<(String, (String, &'static str))>,
+) {
+    match value {
+        serde_yaml::Value::Sequence(seq) => {
+            for item in seq {
+                // 不展开嵌套序列，避免同一路径混入多层结构
+                if !item.is_sequence() {
+                    collect_values(path, item, values);
+                }
+            }
+        }
+        serde_yaml::Value::Mapping(mapping) => {
+            for (key, item) in mapping {
+                if let Some(key) = key.as_str() {
+                    collect_values(&format!("{}.{}", path, key), item, values);
+                }
+            }
+        }
+        _ => {
+            if let Some(scalar) = scalar_value(value) {
+                if !scalar.0.is_empty() {
+                    values.push((path.to_string(), scalar));
+                }
+            }
+        }
+    }
+}
+
+/// 所有已打开工作区的内存索引（以工作区根路径为键）
+#[derive(Default)]
+pub struct WorkspaceIndex {
+    roots: Mutex<HashMap<String, RootIndex>>,
+    /// 每个已建立索引的工作区的文件监听，外部修改（git pull、其他编辑器）由此更新索引
+    watchers: Mutex<HashMap<String, RecommendedWatcher>>,
+    /// 已安排延迟持久化、尚未写入的工作区
+    pending_persist: Mutex<HashSet<String>>,
+    /// 正在全量扫描的工作区及扫描期间变化的路径，扫描完成后重新应用这些变化
+    scanning: Mutex<HashMap<String, HashSet<PathBuf>>>,
+}
+
+/// 快速打开的单条结果
+#[derive(Serialize)]
+pub struct QuickOpenResult {
+    pub path: String,
+    pub relative_path: String,
+    pub title: Option<String>,
+    pub score: i64,
+    /// `relative_path` 中匹配字符的位置（按字符计）
+    pub path_matches: Vec<usize>,
+    /// `title` 中匹配字符的位置（按字符计）
+    pub title_matches: Vec<usize>,
+}
+
+fn is_ignored_name(name: &str) -> bool {
+    name.starts_with('.') || IGNORED_DIRS.contains(&name)
+}
+
+fn is_ignored_dir(entry: &walkdir::DirEntry) -> bool {
+    if entry.depth() == 0 || !entry.file_type().is_dir() {
+        return false;
+    }
+    is_ignored_name(&entry.file_name().to_string_lossy())
+}
+
+/// 路径是否位于扫描时跳过的目录中（或本身是隐藏文件）
+fn is_ignored_path(root: &Path, path: &Path) -> bool {
+    path.strip_prefix(root).is_ok_and(|relative| {
+        relative
+            .components()
+            .any(|c| is_ignored_name(&c.as_os_str().to_string_lossy()))
+    })
+}
+
+fn is_markdown(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|s| s.to_str()),
+        Some("md") | Some("markdown")
+    )
+}
+
+fn relative_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn read_frontmatter(path: &Path) -> Option<Frontmatter> {
+    if !is_markdown(path) {
+        return None;
+    }
+    let content = fs::read_to_string(path).ok()?;
+    extract_frontmatter(&content)
+}
+
+/// 为单个文件生成索引条目。mtime 与大小未变化时复用旧条目，避免重新读取文件。
+fn index_file(
+    root: &Path,
+    path: &Path,
+    metadata: &fs::Metadata,
+    previous: Option<&IndexEntry>,
+) -> IndexEntry {
+    let modified = metadata.modified().ok();
+    let size = metadata.len();
+    if let Some(previous) = previous {
+        if previous.modified == modified && previous.size == size {
+            return previous.clone();
+        }
+    }
+    IndexEntry::new(
+        relative_path(root, path),
+        read_frontmatter(path),
+        modified,
+        size,
+    )
+}
+
+/// 扫描 `dir` 下的所有文件，未变化的文件复用 `previous` 中的条目。
+fn scan_dir(
+    root: &Path,
+    dir: &Path,
+    previous: &HashMap<PathBuf, IndexEntry>,
+) -> HashMap<PathBuf, IndexEntry> {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_entry(|e| !is_ignored_dir(e))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| {
+            let metadata = e.metadata().ok()?;
+            let entry = index_file(root, e.path(), &metadata, previous.get(e.path()));
+            Some((e.into_path(), entry))
+        })
+        .collect()
+}
+
+fn to_epoch(time: SystemTime) -> Option<(u64, u32)> {
+    time.duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| (d.as_secs(), d.subsec_nanos()))
+}
+
+fn from_epoch((secs, nanos): (u64, u32)) -> SystemTime {
+    UNIX_EPOCH + Duration::new(secs, nanos)
+}
+
+/// 从存储中读取上次持久化的索引，作为增量扫描的基准。
+fn load_persisted(app: &AppHandle, root: &str) -> HashMap<PathBuf, IndexEntry> {
+    let Ok(store) = app.store(INDEX_STORE) else {
+        return HashMap::new();
+    };
+    let persisted: HashMap<String, PersistedEntry> =
+        serde_json::from_value(store.get(root).unwrap_or_default()).unwrap_or_default();
+    persisted
+        .into_iter()
+        .filter_map(|(relative, entry)| {
+            // 无法解析的条目丢弃，扫描时重新读取该文件
+            let frontmatter = match entry.frontmatter {
+                Some(yaml) => Some(serde_yaml::from_str(&yaml).ok()?),
+                None => None,
+            };
+            let path = Path::new(root).join(&relative);
+            let entry = IndexEntry::new(
+                relative,
+                frontmatter,
+                entry.modified.map(from_epoch),
+                entry.size,
+            );
+            Some((path, entry))
+        })
+        .collect()
+}
+
+/// 将工作区索引中的 Markdown 条目持久化到存储。
+pub(crate) fn persist(app: &AppHandle, root: &str) -> Result<(), String> {
+    let persisted = app
+        .state::<WorkspaceIndex>()
+        .with_entries(root, |entries| {
+            entries
+                .iter()
+                .filter(|(path, _)| is_markdown(path))
+                .filter_map(|(_, entry)| {
+                    let frontmatter = match entry.frontmatter.as_deref() {
+                        Some(frontmatter) => Some(serde_yaml::to_string(frontmatter).ok()?),
+                        None => None,
+                    };
+                    Some((
+                        entry.relative_path.clone(),
+                        PersistedEntry {
+                            modified: entry.modified.and_then(to_epoch),
+                            size: entry.size,
+                            frontmatter,
+                        },
+                    ))
+                })
+                .collect::<HashMap<_, _>>()
+        })?;
+
+    let store = app
+        .store(INDEX_STORE)
+        .map_err(|e| format!("Failed to open index store: {}", e))?;
+    store.set(
+        root,
+        serde_json::to_value(persisted).map_err(|e| format!("Serialization error: {}", e))?,
+    );
+    store
+        .save()
+        .map_err(|e| format!("Failed to save index store: {}", e))
+}
+
+impl WorkspaceIndex {
+    fn contains(&self, root: &str) -> Result<bool, String> {
+        let roots = self.roots.lock().map_err(|e| e.to_string())?;
+        Ok(roots.contains_key(root))
+    }
+
+    fn is_watched(&self, root: &str) -> Result<bool, String> {
+        let watchers = self.watchers.lock().map_err(|e| e.to_string())?;
+        Ok(watchers.contains_key(root))
+    }
+
+    /// 索引过期时标记为正在刷新并返回 true，由调用方在后台刷新。
+    /// 有文件监听的工作区不会过期。
+    fn mark_stale(&self, root: &str) -> Result<bool, String> {
+        if self.is_watched(root)? {
+            return Ok(false);
+        }
+        let mut roots = self.roots.lock().map_err(|e| e.to_string())?;
+        Ok(match roots.get_mut(root) {
+            Some(index) if !index.refreshing && index.refreshed_at.elapsed() > STALE_AFTER => {
+                index.refreshing = true;
+                true
+            }
+            _ => false,
+        })
+    }
+
+    /// 重新扫描整个工作区，只重新读取 mtime 或大小发生变化的文件。
+    /// `previous` 为空时使用内存中的现有索引作为基准。
+    fn rebuild(
+        &self,
+        root: &str,
+        previous: Option<HashMap<PathBuf, IndexEntry>>,
+    ) -> Result<(), String> {
+        let refresh = previous.is_none();
+        let previous = match previous {
+            Some(previous) => previous,
+            None => {
+                let roots = self.roots.lock().map_err(|e| e.to_string())?;
+                roots
+                    .get(root)
+                    .map(|index| index.entries.clone())
+                    .unwrap_or_default()
+            }
+        };
+        self.scanning
+            .lock()
+            .map_err(|e| e.to_string())?
+            .entry(root.to_string())
+            .or_default();
+        // 在锁外扫描，避免阻塞其他工作区的查询
+        let entries = scan_dir(Path::new(root), Path::new(root), &previous);
+
+        let mut roots = self.roots.lock().map_err(|e| e.to_string())?;
+        let changed = self
+            .scanning
+            .lock()
+            .map_err(|e| e.to_string())?
+            .remove(root)
+            .unwrap_or_default();
+        // 后台刷新期间工作区已关闭
+        if refresh && !roots.contains_key(root) {
+            return Ok(());
+        }
+        let mut index = RootIndex::new(entries);
+        // 扫描期间变化的路径可能已被扫描到旧的状态，按磁盘上的现状重新更新
+        for path in changed {
+            update_root(Path::new(root), &mut index, &path);
+        }
+        roots.insert(root.to_string(), index);
+        Ok(())
+    }
+
+    /// 文件或目录被创建、修改、重命名或删除后更新所有包含它的工作区索引，
+    /// 并就地更新建议计数。正在扫描的工作区记下该路径，扫描完成后再更新。
+    pub fn update_path(&self, path: &Path) {
+        let Ok(mut roots) = self.roots.lock() else {
+            return;
+        };
+        if let Ok(mut scanning) = self.scanning.lock() {
+            for (root, changed) in scanning.iter_mut() {
+                if path.starts_with(root) {
+                    changed.insert(path.to_path_buf());
+                }
+            }
+        }
+        for (root, index) in roots.iter_mut() {
+            let root = Path::new(root);
+            if path.starts_with(root) {
+                update_root(root, index, path);
+            }
+        }
+    }
+
+    /// 工作区的最后一个窗口关闭后停止监听并释放其索引
+    pub fn close_root(&self, root: &str) {
+        if let Ok(mut watchers) = self.watchers.lock() {
+            watchers.remove(root);
+        }
+        if let Ok(mut roots) = self.roots.lock() {
+            roots.remove(root);
+        }
+    }
+
+    /// 在闭包中只读访问某个工作区的索引条目。
+    pub fn with_entries<T>(
+        &self,
+        root: &str,
+        f: impl FnOnce(&HashMap<PathBuf, IndexEntry>) -> T,
+    ) -> Result<T, String> {
+        let roots = self.roots.lock().map_err(|e| e.to_string())?;
+        let entries = roots
+            .get(root)
+            .map(|index| &index.entries)
+            .ok_or_else(|| format!("Workspace is not indexed: {}", root))?;
+        Ok(f(entries))
+    }
+
+    /// 在闭包中只读访问某个工作区的整个索引（含建议计数）。
+    pub(crate) fn with_root<T>(
+        &self,
+        root: &str,
+        f: impl FnOnce(&RootIndex) -> T,
+    ) -> Result<T, String> {
+        let roots = self.roots.lock().map_err(|e| e.to_string())?;
+        let index = roots
+            .get(root)
+            .ok_or_else(|| format!("Workspace is not indexed: {}", root))?;
+        Ok(f(index))
+    }
+
+    pub(crate) fn suggestions(&self, root: &str) -> Result<FrontmatterSuggestions, String> {
+        let roots = self.roots.lock().map_err(|e| e.to_string())?;
+        roots
+            .get(root)
+            .map(|index| index.suggestions())
+            .ok_or_else(|| format!("Workspace is not indexed: {}", root))
+    }
+}
+
+/// 先移除 `path`（及其子路径）下的旧条目，再按磁盘上的现状重新加入
+fn update_root(root: &Path, index: &mut RootIndex, path: &Path) {
+    let previous = index.remove_under(path);
+    match fs::metadata(path) {
+        Ok(metadata) if metadata.is_dir() => {
+            for (p, entry) in scan_dir(root, path, &previous) {
+                index.insert(p, entry);
+            }
+        }
+        Ok(metadata) => {
+            let entry = index_file(root, path, &metadata, None);
+            index.insert(path.to_path_buf(), entry);
+        }
+        Err(_) => {}
+    }
+}
+
+/// 文件发生变化后更新内存索引，供其他命令在写入文件后调用。
+pub(crate) fn notify_path_changed(app: &AppHandle, path: &Path) {
+    if let Some(index) = app.try_state::<WorkspaceIndex>() {
+        index.update_path(path);
+    }
+}
+
+/// 处理文件监听的事件：逐个路径更新索引；事件丢失或监听出错时重新扫描整个工作区
+fn handle_watch_event(app: &AppHandle, root: &str, event: notify::Result<notify::Event>) {
+    let index = app.state::<WorkspaceIndex>();
+    let event = match event {
+        Ok(event) if !event.need_rescan() => event,
+        Ok(_) => {
+            match index.rebuild(root, None) {
+                Ok(()) => schedule_persist(app, root),
+                Err(e) => log::warn!("Failed to rescan workspace {}: {}", root, e),
+            }
+            return;
+        }
+        Err(e) => {
+            log::warn!("File watcher error in {}: {}", root, e);
+            return;
+        }
+    };
+    if event.kind.is_access() {
+        return;
+    }
+    let mut changed = false;
+    for path in &event.paths {
+        if !is_ignored_path(Path::new(root), path) {
+            notify_path_changed(app, path);
+            changed = true;
+        }
+    }
+    if changed {
+        schedule_persist(app, root);
+    }
+}
+
+/// 为工作区启动文件监听；失败时记录日志，由定时重新扫描兜底。
+fn watch_root(app: &AppHandle, root: &str) -> Result<(), String> {
+    let index = app.state::<WorkspaceIndex>();
+    let mut watchers = index.watchers.lock().map_err(|e| e.to_string())?;
+    if watchers.contains_key(root) {
+        return Ok(());
+    }
+
+    let handler_app = app.clone();
+    let handler_root = root.to_string();
+    let watcher = notify::recommended_watcher(move |event| {
+        handle_watch_event(&handler_app, &handler_root, event)
+    })
+    .and_then(|mut watcher| {
+        watcher.watch(Path::new(root), RecursiveMode::Recursive)?;
+        Ok(watcher)
+    });
+    match watcher {
+        Ok(watcher) => {
+            watchers.insert(root.to_string(), watcher);
+        }
+        Err(e) => log::warn!("Failed to watch workspace {}: {}", root, e),
+    }
+    Ok(())
+}
+
+/// 确保工作区已建立索引。首次访问时以持久化的索引为基准同步扫描，
+/// 只解析有变化的文件，并开始监听工作区中的文件变化；
+/// 无法监听时索引过期后在后台刷新，不阻塞当前调用。
+pub(crate) fn ensure_index(app: &AppHandle, root: &str) -> Result<(), String> {
+    let index = app.state::<WorkspaceIndex>();
+    if !index.contains(root)? {
+        index.rebuild(root, Some(load_persisted(app, root)))?;
+        watch_root(app, root)?;
+        return persist(app, root);
+    }
+
+    if index.mark_stale(root)? {
+        let app = app.clone();
+        let root = root.to_string();
+        std::thread::spawn(move || {
+            let result = app
+                .state::<WorkspaceIndex>()
+                .rebuild(&root, None)
+                .and_then(|_| persist(&app, &root));
+            if let Err(e) = result {
+                log::warn!("Failed to refresh workspace index {}: {}", root, e);
+            }
+        });
+    }
+    Ok(())
+}
+
+/// 立即重新扫描工作区（只解析有变化的文件）并持久化索引。
+pub(crate) fn refresh_index(app: &AppHandle, root: &str) -> Result<(), String> {
+    let index = app.state::<WorkspaceIndex>();
+    if index.contains(root)? {
+        index.rebuild(root, None)?;
+    } else {
+        index.rebuild(root, Some(load_persisted(app, root)))?;
+    }
+    persist(app, root)
+}
+
+/// 延迟持久化工作区的索引与建议：`PERSIST_DELAY` 内的多次调用只写入一次。
+/// 写入在后台线程中进行，失败时记录日志，下次修改时会再次尝试。
+pub(crate) fn schedule_persist(app: &AppHandle, root: &str) {
+    let index = app.state::<WorkspaceIndex>();
+    let Ok(mut pending) = index.pending_persist.lock() else {
+        return;
+    };
+    if !pending.insert(root.to_string()) {
+        return;
+    }
+    drop(pending);
+
+    let app = app.clone();
+    let root = root.to_string();
+    std::thread::spawn(move || {
+        std::thread::sleep(PERSIST_DELAY);
+        // 先移出等待列表，写入期间的修改会安排下一次写入
+        let index = app.state::<WorkspaceIndex>();
+        if let Ok(mut pending) = index.pending_persist.lock() {
+            pending.remove(&root);
+        }
+        // 等待期间工作区已关闭
+        if !index.contains(&root).unwrap_or(false) {
+            return;
+        }
+        let result = persist(&app, &root).and_then(|_| store_suggestions(&app, &root));
+        if let Err(e) = result {
+            log::warn!("Failed to persist workspace index {}: {}", root, e);
+        }
+    });
+}
+
+/// 将索引中的建议计数写入 frontmatter 存储，供 `load_frontmatter_suggestions` 读取。
+pub(crate) fn store_suggestions(app: &AppHandle, root: &str) -> Result<(), String> {
+    let suggestions = app.state::<WorkspaceIndex>().suggestions(root)?;
+    let store = app
+        .store(".frontmatter.dat")
+        .map_err(|e| format!("Failed to open frontmatter store: {}", e))?;
+    store.set(
+        suggestions_key(Some(root)),
+        serde_json::to_value(suggestions).map_err(|e| format!("Serialization error: {}", e))?,
+    );
+    store
+        .save()
+        .map_err(|e| format!("Failed to save suggestions: {}", e))
+}
+
+/// 返回工作区中所有 Markdown 文件的路径（按路径排序），必要时先建立索引。
+pub(crate) fn markdown_files(app: &AppHandle, root: &str) -> Result<Vec<PathBuf>, String> {
+    ensure_index(app, root)?;
+    let mut files = app
+        .state::<WorkspaceIndex>()
+        .with_entries(root, |entries| {
+            entries
+                .keys()
+                .filter(|p| is_markdown(p))
+                .cloned()
+                .collect::<Vec<_>>()
+        })?;
+    files.sort();
+    Ok(files)
+}
+
+/// 返回工作区中所有 Markdown 文件及其 frontmatter（按路径排序），必要时先建立索引。
+pub(crate) fn markdown_frontmatter(
+    app: &AppHandle,
+    root: &str,
+) -> Result<Vec<MarkdownFrontmatter>, String> {
+    ensure_index(app, root)?;
+    let mut files = app
+        .state::<WorkspaceIndex>()
+        .with_entries(root, |entries| {
+            entries
+                .iter()
+                .filter(|(p, _)| is_markdown(p))
+                .map(|(p, e)| (p.clone(), e.frontmatter.clone()))
+                .collect::<Vec<_>>()
+        })?;
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(files)
+}
+
+/// Tauri 命令：更新指定文件在 frontmatter 索引中的条目并刷新建议，
+/// 保存单篇文章后调用，无需重新扫描整个工作区。索引与建议稍后合并写入存储。
+#[tauri::command]
+pub async fn update_frontmatter_index(
+    app: AppHandle,
+    window: Window,
+    paths: Vec<String>,
+) -> Result<(), String> {
+    let workspace = require_workspace(&window)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        ensure_index(&app, &workspace)?;
+        let index = app.state::<WorkspaceIndex>();
+        for path in &paths {
+            index.update_path(Path::new(path));
+        }
+        schedule_persist(&app, &workspace);
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Background thread failed: {}", e))?
+}
+
+const SCORE_MATCH: i64 = 16;
+const BONUS_BOUNDARY: i64 = 10;
+const BONUS_CAMEL: i64 = 6;
+const BONUS_CONSECUTIVE: i64 = 8;
+const BONUS_FIRST_CHAR: i64 = 4;
+const PENALTY_GAP: i64 = 1;
+
+fn is_separator(c: char) -> bool {
+    matches!(c, '/' | '\\' | '-' | '_' | ' ' | '.')
+}
+
+/// 位置 `j` 的字符作为匹配时的额外得分：位于单词开头或驼峰边界时加分。
+fn position_bonus(original: &[char], j: usize) -> i64 {
+    if j == 0 {
+        return BONUS_BOUNDARY;
+    }
+    let prev = original[j - 1];
+    let cur = original[j];
+    if is_separator(prev) {
+        BONUS_BOUNDARY
+    } else if prev.is_lowercase() && cur.is_uppercase() {
+        BONUS_CAMEL
+    } else {
+        0
+    }
+}
+
+/// 模糊匹配 `query`（已转为小写的字符）与文本，返回得分与匹配位置。
+/// 使用动态规划选出得分最高的匹配方式；不是子序列时返回 None。
+pub(crate) fn fuzzy_match(
+    query: &[char],
+    text_lower: &[char],
+    text: &str,
+) -> Option<(i64, Vec<usize>)> {
+    if query.is_empty() {
+        return Some((0, Vec::new()));
+    }
+
+    // 快速检查是否为子序列，绝大多数条目在这里被排除
+    let mut qi = 0;
+    for &c in text_lower {
+        if c == query[qi] {
+            qi += 1;
+            if qi == query.len() {
+                break;
+            }
+        }
+    }
+    if qi < query.len() {
+        return None;
+    }
+
+    let original: Vec<char> = text.chars().collect();
+    // 小写转换可能改变字符数量（极少见），此时退回到小写文本计算边界
+    let original = if original.len() == text_lower.len() {
+        original
+    } else {
+        text_lower.to_vec()
+    };
+
+    let n = text_lower.len();
+    let m = query.len();
+    const NONE: i64 = i64::MIN / 2;
+    // score[i][j]：query[..=i] 已匹配且 query[i] 落在 j 处时的最高得分
+    let mut score = vec![vec![NONE; n]; m];
+    let mut back = vec![vec![usize::MAX; n]; m];
+
+    for j in 0..n {
+        if text_lower[j] == query[0] {
+            let first = if j == 0 { BONUS_FIRST_CHAR } else { 0 };
+            score[0][j] = SCORE_MATCH + position_bonus(&original, j) + first;
+        }
+    }
+
+    for i in 1..m {
+        // best_gap：前一个字符落在 j-2 或更早位置时（扣除间隔惩罚后）的最高得分
+        let mut best_gap = NONE;
+        let mut best_gap_at = usize::MAX;
+        for j in 1..n {
+            if j >= 2 && score[i - 1][j - 2] > NONE {
+                let candidate = score[i - 1][j - 2] - PENALTY_GAP;
+                if candidate > best_gap - PENALTY_GAP {
+                    best_gap = candidate;
+                    best_gap_at = j - 2;
+                } else {
+                    best_gap -= PENALTY_GAP;
+                }
+            } else if best_gap > NONE {
+                best_gap -= PENALTY_GAP;
+            }
+
+            if text_lower[j] != query[i] {
+                continue;
+            }
+
+            let base = SCORE_MATCH + position_bonus(&original, j);
+            let consecutive = if score[i - 1][j - 1] > NONE {
+                score[i - 1][j - 1] + BONUS_CONSECUTIVE
+            } else {
+                NONE
+            };
+            if consecutive >= best_gap && consecutive > NONE {
+                score[i][j] = base + consecutive;
+                back[i][j] = j - 1;
+            } else if best_gap > NONE {
+                score[i][j] = base + best_gap;
+                back[i][j] = best_gap_at;
+            }
+        }
+    }
+
+    let (mut j, best) = score[m - 1]
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| **s > NONE)
+        .max_by_key(|(j, s)| (**s, std::cmp::Reverse(*j)))
+        .map(|(j, s)| (j, *s))?;
+
+    let mut positions = vec![0; m];
+    for i in (0..m).rev() {
+        positions[i] = j;
+        if i > 0 {
+            j = back[i][j];
+        }
+    }
+    Some((best, positions))
+}
+
+/// Tauri 命令：在当前窗口的工作区中模糊查找文件，匹配相对路径与 frontmatter 标题。
+/// 首次调用时需要建立索引，在阻塞线程中执行以避免阻塞主线程。
+#[tauri::command]
+pub async fn quick_open(
+    app: AppHandle,
+    window: Window,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<QuickOpenResult>, String> {
+    let workspace = require_workspace(&window)?;
+    tauri::async_runtime::spawn_blocking(move || search_index(&app, &workspace, &query, limit))
+        .await
+        .map_err(|e| format!("Background thread failed: {}", e))?
+}
+
+fn search_index(
+    app: &AppHandle,
+    workspace: &str,
+    query: &str,
+    limit: Option<usize>,
+) -> Result<Vec<QuickOpenResult>, String> {
+    ensure_index(app, workspace)?;
+
+    let query: Vec<char> = query
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+
+    let mut results = app
+        .state::<WorkspaceIndex>()
+        .with_entries(workspace, |entries| {
+            entries
+                .iter()
+                .filter_map(|(path, entry)| {
+                    let path_match =
+                        fuzzy_match(&query, &entry.relative_lower, &entry.relative_path);
+                    let title_match = entry
+                        .title
+                        .as_deref()
+                        .and_then(|title| fuzzy_match(&query, &entry.title_lower, title));
+
+                    let (score, path_matches, title_matches) = match (path_match, title_match) {
+                        (None, None) => return None,
+                        (Some((ps, pm)), None) => (ps, pm, Vec::new()),
+                        (None, Some((ts, tm))) => (ts, Vec::new(), tm),
+                        (Some((ps, pm)), Some((ts, tm))) => {
+                            if ts > ps {
+                                (ts, Vec::new(), tm)
+                            } else {
+                                (ps, pm, Vec::new())
+                            }
+                        }
+                    };
+
+                    Some(QuickOpenResult {
+                        path: path.to_string_lossy().to_string(),
+                        relative_path: entry.relative_path.clone(),
+                        title: entry.title.clone(),
+                        score,
+                        path_matches,
+                        title_matches,
+                    })
+                })
+                .collect::<Vec<_>>()
+        })?;
+
+    // 得分相同时，路径更短的排在前面
+    results.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.relative_path.len().cmp(&b.relative_path.len()))
+            .then_with(|| a.relative_path.cmp(&b.relative_path))
+    });
+    results.truncate(limit);
+    Ok(results)
+}
+
+/// Tauri 命令：立即重新扫描当前窗口的工作区索引（例如外部工具批量修改文件后）。
+#[tauri::command]
+pub async fn refresh_workspace_index(app: AppHandle, window: Window) -> Result<(), String> {
+    let workspace = require_workspace(&window)?;
+    tauri::async_runtime::spawn_blocking(move || refresh_index(&app, &workspace))
+        .await
+        .map_err(|e| format!("Background thread failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.to_lowercase().chars().collect()
+    }
+
+    fn positions(query: &str, text: &str) -> Option<Vec<usize>> {
+        fuzzy_match(&chars(query), &chars(text), text).map(|(_, p)| p)
+    }
+
+    fn score(query: &str, text: &str) -> i64 {
+        fuzzy_match(&chars(query), &chars(text), text).unwrap().0
+    }
+
+    #[test]
+    fn fuzzy_match_requires_a_subsequence() {
+        assert_eq!(positions("", "anything"), Some(Vec::new()));
+        assert_eq!(positions("abc", "acb"), None);
+        assert_eq!(positions("PST", "posts/first.md"), Some(vec![0, 2, 3]));
+    }
+
+    #[test]
+    fn fuzzy_match_prefers_boundaries_and_runs() {
+        assert_eq!(positions("fm", "docs/front-matter.md"), Some(vec![5, 11]));
+        assert_eq!(positions("post", "a/posts/b.md"), Some(vec![2, 3, 4, 5]));
+        assert!(score("gs", "getting-started") > score("gs", "graphs"));
+        assert_eq!(positions("qo", "src/QuickOpen.vue"), Some(vec![4, 9]));
+    }
+
+    #[test]
+    fn string_type_recognises_dates_and_times() {
+        assert_eq!(string_type("2024-01-05"), "date");
+        assert_eq!(string_type("2024-01-05T10:00:00Z"), "dateandtime");
+        assert_eq!(string_type("2024-01-05 10:00"), "dateandtime");
+        assert_eq!(string_type("10:30"), "time");
+        assert_eq!(string_type("10:30:15"), "time");
+        assert_eq!(string_type("2024-01-05x"), "string");
+        assert_eq!(string_type("10:30 am"), "string");
+    }
+
+    #[test]
+    fn suggestion_values_flatten_nested_fields() {
+        let frontmatter: Frontmatter = serde_yaml::from_str(
+            "tags: [rust, '']\nauthor:\n  name: Ann\nauthors:\n  - name: Bo\ndraft: false",
+        )
+        .unwrap();
+        let mut values = suggestion_values(&frontmatter);
+        values.sort();
+        assert_eq!(
+            values,
+            [
+                ("author.name".to_string(), ("Ann".to_string(), "string")),
+                ("authors.name".to_string(), ("Bo".to_string(), "string")),
+                ("draft".to_string(), ("false".to_string(), "boolean")),
+                ("tags".to_string(), ("rust".to_string(), "string")),
+            ]
+        );
+    }
+
+    #[test]
+    fn rebuild_and_update_path_keep_counts_in_sync() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_string_lossy().to_string();
+        let post = dir.path().join("a.md");
+        fs::write(&post, "---\ntags: [rust]\n---\n").unwrap();
+        fs::create_dir(dir.path().join("node_modules")).unwrap();
+        fs::write(
+            dir.path().join("node_modules/b.md"),
+            "---\ntags: [js]\n---\n",
+        )
+        .unwrap();
+
+        let index = WorkspaceIndex::default();
+        index.rebuild(&root, Some(HashMap::new())).unwrap();
+        assert_eq!(index.with_entries(&root, |e| e.len()).unwrap(), 1);
+        let tags = |index: &WorkspaceIndex| {
+            let suggestions = index.suggestions(&root).unwrap();
+            let mut tags: Vec<String> = suggestions
+                .field_suggestions
+                .get("tags")
+                .map(|values| values.iter().map(|s| s.value.clone()).collect())
+                .unwrap_or_default();
+            tags.sort();
+            tags
+        };
+        assert_eq!(tags(&index), ["rust"]);
+
+        fs::write(&post, "---\ntags: [go]\n---\nchanged").unwrap();
+        index.update_path(&post);
+        assert_eq!(tags(&index), ["go"]);
+
+        fs::remove_file(&post).unwrap();
+        index.update_path(&post);
+        assert!(tags(&index).is_empty());
+
+        index.close_root(&root);
+        assert!(!index.contains(&root).unwrap());
+        // 关闭后完成的后台刷新不会重新加入该工作区
+        index.rebuild(&root, None).unwrap();
+        assert!(!index.contains(&root).unwrap());
+    }
+}
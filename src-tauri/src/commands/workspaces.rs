@@ -0,0 +1,254 @@
+use super::get_file_tree::stored_workspace;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, State, WebviewUrl, WebviewWindowBuilder, Window};
+use tauri_plugin_store::StoreExt;
+
+/// 最多保留的未固定的最近工作区数量（固定的工作区不计入）
+const MAX_RECENT: usize = 20;
+
+/// 最近打开的工作区
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentWorkspace {
+    pub path: String,
+    pub name: String,
+    /// 最后一次打开的时间（Unix 时间戳，秒）
+    pub last_opened: u64,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// 每个窗口当前打开的工作区（以窗口 label 为键）
+#[derive(Default)]
+pub struct WorkspaceState {
+    windows: Mutex<HashMap<String, String>>,
+    next_window: AtomicUsize,
+}
+
+impl WorkspaceState {
+    /// 记录某个窗口打开的工作区
+    pub fn set(&self, label: &str, path: &str) {
+        if let Ok(mut windows) = self.windows.lock() {
+            windows.insert(label.to_string(), path.to_string());
+        }
+    }
+
+    pub fn get(&self, label: &str) -> Option<String> {
+        self.windows
+            .lock()
+            .ok()
+            .and_then(|windows| windows.get(label).cloned())
+    }
+
+    /// 窗口关闭时移除其工作区记录，返回该窗口打开的工作区
+    pub fn remove(&self, label: &str) -> Option<String> {
+        self.windows
+            .lock()
+            .ok()
+            .and_then(|mut windows| windows.remove(label))
+    }
+
+    /// 是否仍有窗口打开了该工作区
+    pub fn is_open(&self, path: &str) -> bool {
+        self.window_for(path).is_some()
+    }
+
+    fn window_for(&self, path: &str) -> Option<String> {
+        self.windows.lock().ok().and_then(|windows| {
+            windows
+                .iter()
+                .find(|(_, p)| p.as_str() == path)
+                .map(|(label, _)| label.clone())
+        })
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn load_recent(app: &AppHandle) -> Result<Vec<RecentWorkspace>, String> {
+    let store = app
+        .store(".settings.dat")
+        .map_err(|e| format!("无法访问存储: {}", e))?;
+    Ok(
+        serde_json::from_value(store.get("recentWorkspaces").unwrap_or_default())
+            .unwrap_or_default(),
+    )
+}
+
+fn save_recent(app: &AppHandle, mut recent: Vec<RecentWorkspace>) -> Result<(), String> {
+    // 固定的排在前面，其余按最后打开时间倒序
+    recent.sort_by(|a, b| {
+        b.pinned
+            .cmp(&a.pinned)
+            .then_with(|| b.last_opened.cmp(&a.last_opened))
+    });
+
+    // 只限制未固定的条目数量
+    let mut unpinned = 0;
+    recent.retain(|w| {
+        if w.pinned {
+            return true;
+        }
+        unpinned += 1;
+        unpinned <= MAX_RECENT
+    });
+
+    let store = app
+        .store(".settings.dat")
+        .map_err(|e| format!("无法访问存储: {}", e))?;
+    store.set(
+        "recentWorkspaces",
+        serde_json::to_value(recent).map_err(|e| format!("无法序列化最近工作区: {}", e))?,
+    );
+    store.save().map_err(|e| format!("无法保存存储: {}", e))
+}
+
+/// 将工作区记录为最近打开，并更新最后打开时间。
+pub(crate) fn record_recent_workspace(app: &AppHandle, path: &str) -> Result<(), String> {
+    let mut recent = load_recent(app)?;
+    let name = Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string());
+
+    match recent.iter_mut().find(|w| w.path == path) {
+        Some(existing) => {
+            existing.last_opened = now_secs();
+            existing.name = name;
+        }
+        None => recent.push(RecentWorkspace {
+            path: path.to_string(),
+            name,
+            last_opened: now_secs(),
+            pinned: false,
+        }),
+    }
+
+    save_recent(app, recent)
+}
+
+/// 获取窗口当前的工作区；窗口没有单独记录时回退到存储中的工作区路径。
+pub(crate) fn window_workspace(window: &Window) -> Result<Option<String>, String> {
+    let app = window.app_handle();
+    if let Some(path) = app.state::<WorkspaceState>().get(window.label()) {
+        return Ok(Some(path));
+    }
+    stored_workspace(app)
+}
+
+/// Tauri 命令：列出最近打开的工作区。`prune` 为 true 时同时移除已不存在的目录。
+#[tauri::command]
+pub fn list_recent_workspaces(
+    app: AppHandle,
+    prune: Option<bool>,
+) -> Result<Vec<RecentWorkspace>, String> {
+    let mut recent = load_recent(&app)?;
+    if prune.unwrap_or(false) {
+        let before = recent.len();
+        recent.retain(|w| Path::new(&w.path).is_dir());
+        if recent.len() != before {
+            save_recent(&app, recent.clone())?;
+        }
+    }
+
+    recent.sort_by(|a, b| {
+        b.pinned
+            .cmp(&a.pinned)
+            .then_with(|| b.last_opened.cmp(&a.last_opened))
+    });
+    Ok(recent)
+}
+
+/// Tauri 命令：固定或取消固定一个最近工作区。
+#[tauri::command]
+pub fn pin_workspace(app: AppHandle, path: String, pinned: bool) -> Result<(), String> {
+    let mut recent = load_recent(&app)?;
+    let workspace = recent
+        .iter_mut()
+        .find(|w| w.path == path)
+        .ok_or_else(|| format!("工作区不在最近列表中: {}", path))?;
+    workspace.pinned = pinned;
+    save_recent(&app, recent)
+}
+
+/// Tauri 命令：从最近列表中移除一个工作区（不会删除目录本身）。
+#[tauri::command]
+pub fn remove_recent_workspace(app: AppHandle, path: String) -> Result<(), String> {
+    let mut recent = load_recent(&app)?;
+    recent.retain(|w| w.path != path);
+    save_recent(&app, recent)
+}
+
+/// Tauri 命令：移除所有已不存在的工作区，返回被移除的路径。
+#[tauri::command]
+pub fn prune_recent_workspaces(app: AppHandle) -> Result<Vec<String>, String> {
+    let recent = load_recent(&app)?;
+    let (kept, removed): (Vec<_>, Vec<_>) = recent
+        .into_iter()
+        .partition(|w| Path::new(&w.path).is_dir());
+    if !removed.is_empty() {
+        save_recent(&app, kept)?;
+    }
+    Ok(removed.into_iter().map(|w| w.path).collect())
+}
+
+/// Tauri 命令：获取当前窗口打开的工作区。
+#[tauri::command]
+pub fn get_window_workspace(window: Window) -> Result<Option<String>, String> {
+    window_workspace(&window)
+}
+
+/// Tauri 命令：在新窗口中打开工作区，返回窗口 label。
+/// 如果已有窗口打开了该工作区，则聚焦该窗口。
+#[tauri::command]
+pub async fn open_workspace_window(
+    app: AppHandle,
+    state: State<'_, WorkspaceState>,
+    path: String,
+) -> Result<String, String> {
+    if !Path::new(&path).is_dir() {
+        return Err(format!("路径不是目录: {}", path));
+    }
+
+    if let Some(label) = state.window_for(&path) {
+        if let Some(existing) = app.get_webview_window(&label) {
+            existing
+                .set_focus()
+                .map_err(|e| format!("无法聚焦窗口: {}", e))?;
+            return Ok(label);
+        }
+        state.remove(&label);
+    }
+
+    let label = format!(
+        "workspace-{}",
+        state.next_window.fetch_add(1, Ordering::SeqCst) + 1
+    );
+    let title = Path::new(&path)
+        .file_name()
+        .map(|n| format!("rsoul - {}", n.to_string_lossy()))
+        .unwrap_or_else(|| "rsoul".to_string());
+
+    // 先记录工作区，新窗口加载后即可通过 get_stored_path 读取
+    state.set(&label, &path);
+    if let Err(e) = WebviewWindowBuilder::new(&app, &label, WebviewUrl::App("index.html".into()))
+        .title(title)
+        .inner_size(800.0, 600.0)
+        .build()
+    {
+        state.remove(&label);
+        return Err(format!("无法创建窗口: {}", e));
+    }
+
+    record_recent_workspace(&app, &path)?;
+    Ok(label)
+}
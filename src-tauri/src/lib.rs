@@ -1,19 +1,45 @@
 mod commands;
+use commands::bulk_edit::{apply_bulk_edit, preview_bulk_edit};
+use commands::duplicate_values::find_duplicate_values;
 use commands::file_operations::{create_file, create_folder, delete_item, rename_item};
+use commands::frontmatter_lint::lint_frontmatter;
+use commands::frontmatter_query::query_frontmatter;
+use commands::frontmatter_values::{apply_value_change, preview_value_change};
 use commands::get_file_content::get_file_content;
 use commands::get_file_tree::{
-    get_file_tree, get_file_tree_from_path, get_stored_path, set_working_directory,
+    get_file_tree, get_file_tree_from_path, get_stored_path, get_tree_sort_order,
+    set_tree_sort_order, set_working_directory,
 };
+use commands::json_schema::{export_json_schema, import_json_schema};
 use commands::lang::{get_lang, set_lang};
 use commands::open_terminal::open_terminal;
+use commands::replace::{preview_replace_in_workspace, replace_in_workspace};
 use commands::save_frontmatter::{
     collect_frontmatter_suggestions, initialize_form_data, load_frontmatter,
-    load_frontmatter_suggestions, save_form_data_to_frontmatter, save_frontmatter,
+    load_frontmatter_for_path, load_frontmatter_suggestions, save_form_data_to_frontmatter,
+    save_frontmatter,
 };
 use commands::save_markdown::{save_markdown, save_markdown_with_frontmatter};
+use commands::schema_files::{export_frontmatter_schema, import_frontmatter_schema};
+use commands::schema_inference::infer_frontmatter_schema;
+use commands::schema_presets::{apply_schema_preset, detect_site_generator, list_schema_presets};
+use commands::search::{cancel_search, search_workspace, SearchState};
 use commands::theme::{get_theme, if_change_dark};
+use commands::undo::{list_undo_entries, undo_change, UndoHistory};
+use commands::value_suggestions::suggest_values;
+use commands::virtual_tree::get_virtual_tree;
+use commands::workspace_config::{
+    get_workspace_config, save_folder_schemas, save_workspace_settings, set_workspace_config_shared,
+};
+use commands::workspace_index::{
+    quick_open, refresh_workspace_index, update_frontmatter_index, WorkspaceIndex,
+};
+use commands::workspaces::{
+    get_window_workspace, list_recent_workspaces, open_workspace_window, pin_workspace,
+    prune_recent_workspaces, remove_recent_workspace, WorkspaceState,
+};
 use tauri::menu::MenuBuilder;
-use tauri::Emitter;
+use tauri::{Emitter, Manager, WindowEvent};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -23,18 +49,61 @@ pub fn run() {
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_shell::init())
+        .manage(WorkspaceState::default())
+        .manage(WorkspaceIndex::default())
+        .manage(SearchState::default())
+        .manage(UndoHistory::default())
         .invoke_handler(tauri::generate_handler![
             get_file_tree,
             get_file_tree_from_path,
             get_stored_path,
             set_working_directory,
+            get_tree_sort_order,
+            set_tree_sort_order,
+            get_virtual_tree,
+            list_recent_workspaces,
+            pin_workspace,
+            remove_recent_workspace,
+            prune_recent_workspaces,
+            get_window_workspace,
+            open_workspace_window,
+            get_workspace_config,
+            save_workspace_settings,
+            save_folder_schemas,
+            set_workspace_config_shared,
+            quick_open,
+            refresh_workspace_index,
+            update_frontmatter_index,
+            search_workspace,
+            cancel_search,
+            query_frontmatter,
+            preview_value_change,
+            apply_value_change,
+            preview_bulk_edit,
+            apply_bulk_edit,
+            find_duplicate_values,
+            infer_frontmatter_schema,
+            export_frontmatter_schema,
+            import_frontmatter_schema,
+            list_schema_presets,
+            detect_site_generator,
+            apply_schema_preset,
+            import_json_schema,
+            export_json_schema,
+            lint_frontmatter,
+            preview_replace_in_workspace,
+            replace_in_workspace,
+            list_undo_entries,
+            undo_change,
             get_file_content,
             save_markdown,
             save_markdown_with_frontmatter,
             save_frontmatter,
             load_frontmatter,
+            load_frontmatter_for_path,
             collect_frontmatter_suggestions,
             load_frontmatter_suggestions,
+            suggest_values,
             initialize_form_data,
             save_form_data_to_frontmatter,
             open_terminal,
@@ -77,6 +146,17 @@ pub fn run() {
 
             Ok(())
         })
+        .on_window_event(|window, event| {
+            if let WindowEvent::Destroyed = event {
+                let workspaces = window.state::<WorkspaceState>();
+                // 工作区的最后一个窗口关闭后停止监听并释放其索引
+                if let Some(root) = workspaces.remove(window.label()) {
+                    if !workspaces.is_open(&root) {
+                        window.state::<WorkspaceIndex>().close_root(&root);
+                    }
+                }
+            }
+        })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -40,9 +40,9 @@ export async function handleSave(
     filePath,
   });
 
-  // 更新 frontmatter 建议
+  // 只更新当前文件的 frontmatter 索引与建议，无需重新扫描整个工作区
   try {
-    await invoke("collect_frontmatter_suggestions");
+    await invoke("update_frontmatter_index", { paths: [filePath] });
   } catch (e) {
     console.warn("更新 frontmatter 建议失败:", e);
   }
@@ -17,6 +17,7 @@ export interface BackendNode {
   is_dir: boolean;
   children?: BackendNode[] | null;
   size?: number | null;
+  ungrouped?: boolean;
 }
 
 /**
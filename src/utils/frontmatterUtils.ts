@@ -3,18 +3,25 @@
 import { invoke } from "@tauri-apps/api/core";
 
 /**
- * 加载frontmatter字段定义schema
+ * 加载frontmatter字段定义schema；传入文件路径时返回该文件所在目录适用的字段定义
  */
-export async function loadFrontmatterSchema(): Promise<
+export async function loadFrontmatterSchema(path?: string): Promise<
   Array<{
     key: number;
     title: string;
     field_type: string;
     save_as_array: boolean;
     quote_strings: boolean;
+    integer?: boolean;
+    date_format?: string;
+    timezone?: string;
+    date_as_string?: boolean;
+    auto?: string | null;
   }>
 > {
-  const loaded = (await invoke("load_frontmatter")) as Array<{
+  const loaded = (await (path
+    ? invoke("load_frontmatter_for_path", { path })
+    : invoke("load_frontmatter"))) as Array<{
     key: number;
     title: string;
     field_type?: string;
@@ -23,6 +30,7 @@ export async function loadFrontmatterSchema(): Promise<
     quote_strings?: boolean;
   }>;
   return loaded.map((f) => ({
+    ...f,
     key: f.key,
     title: f.title,
     field_type: f.field_type ?? f.type ?? "string",
@@ -35,14 +43,108 @@ export async function loadFrontmatterSchema(): Promise<
  * 加载frontmatter字段值建议
  */
 export async function loadFrontmatterSuggestions(): Promise<
-  Record<string, Array<{ value: string; count: number }>>
+  Record<string, Array<{ value: string; value_type?: string; count: number }>>
 > {
   const loadedSuggestions = (await invoke("load_frontmatter_suggestions")) as {
-    field_suggestions: Record<string, Array<{ value: string; count: number }>>;
+    field_suggestions: Record<string, Array<{ value: string; value_type?: string; count: number }>>;
   };
   return loadedSuggestions.field_suggestions;
 }
 
+/**
+ * 按输入内容与当前文章的frontmatter获取排序后的字段值建议
+ */
+export async function suggestValues(
+  field: string,
+  prefix: string,
+  currentFrontmatter: Record<string, unknown>,
+  limit?: number,
+): Promise<
+  Array<{
+    value: string;
+    value_type: string;
+    count: number;
+    last_used: number | null;
+    co_occurrence: number;
+    score: number;
+    matches: number[];
+  }>
+> {
+  return await invoke("suggest_values", {
+    field,
+    prefix,
+    currentFrontmatter,
+    limit,
+  });
+}
+
+/**
+ * 将字段定义导出为 JSON、YAML 或 TOML 文件（按扩展名判断格式）
+ */
+export async function exportFrontmatterSchema(path: string): Promise<void> {
+  await invoke("export_frontmatter_schema", { path });
+}
+
+/**
+ * 从文件导入字段定义，mode 为 merge（按字段名合并）或 replace（全部替换）
+ */
+export async function importFrontmatterSchema(
+  path: string,
+  mode: "merge" | "replace" = "merge",
+): Promise<void> {
+  await invoke("import_frontmatter_schema", { path, mode });
+}
+
+/**
+ * 从 JSON Schema 文件（包括 Astro 导出的 collection schema）导入字段定义
+ */
+export async function importJsonSchema(path: string): Promise<void> {
+  await invoke("import_json_schema", { path, mode: "merge" });
+}
+
+/**
+ * 将字段定义导出为 JSON Schema 文件
+ */
+export async function exportJsonSchema(path: string): Promise<void> {
+  await invoke("export_json_schema", { path });
+}
+
+/**
+ * 保存按目录 glob 指定的字段定义（如 docs/** 与 blog/** 使用不同字段）
+ */
+export async function saveFolderSchemas(
+  folderSchemas: Array<{ pattern: string; fields: Array<Record<string, unknown>> }>,
+): Promise<void> {
+  await invoke("save_folder_schemas", { folderSchemas });
+}
+
+/**
+ * 列出内置的静态站点生成器字段预设
+ */
+export async function listSchemaPresets(): Promise<
+  Array<{ id: string; name: string }>
+> {
+  return await invoke("list_schema_presets");
+}
+
+/**
+ * 根据工作区中的配置文件识别站点生成器，返回对应的预设 id
+ */
+export async function detectSiteGenerator(): Promise<string | null> {
+  const detected = (await invoke("detect_site_generator")) as {
+    preset: string;
+    marker: string;
+  } | null;
+  return detected?.preset ?? null;
+}
+
+/**
+ * 将预设合并到当前字段定义
+ */
+export async function applySchemaPreset(preset: string): Promise<void> {
+  await invoke("apply_schema_preset", { preset, mode: "merge" });
+}
+
 /**
  * 收集frontmatter字段值建议
  */
@@ -77,10 +179,12 @@ export async function initializeFormData(
     quote_strings: boolean;
   }>,
   currentFrontmatter: Record<string, any> = {},
+  path?: string,
 ): Promise<Record<string, any>> {
   const result = await invoke("initialize_form_data", {
     schema,
     currentFrontmatter,
+    path,
   });
   return result as Record<string, any>;
 }
@@ -97,10 +201,12 @@ export async function saveFormDataToFrontmatter(
     quote_strings: boolean;
   }>,
   formData: Record<string, any>,
+  path?: string,
 ): Promise<Record<string, any>> {
   const result = await invoke("save_form_data_to_frontmatter", {
     schema,
     formData,
+    path,
   });
   return result as Record<string, any>;
 }
@@ -110,7 +216,7 @@ export async function saveFormDataToFrontmatter(
  */
 export function getFieldOptions(
   fieldTitle: string,
-  suggestions: Record<string, Array<{ value: string; count: number }>>,
+  suggestions: Record<string, Array<{ value: string; value_type?: string; count: number }>>,
   currentValues?: string[],
 ): Array<{ label: string; value: string }> {
   console.log(
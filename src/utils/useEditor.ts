@@ -108,13 +108,17 @@ export function useEditor(props: EditorProps) {
   // 创建frontmatter
   const createFrontmatter = async () => {
     try {
-      const schema = await loadFrontmatterSchema();
+      const schema = await loadFrontmatterSchema(props.path);
       if (schema.length === 0) {
         message.warning(t("frontmatter.noSchemaWarning"));
         return;
       }
 
-      const emptyFrontmatter = await initializeFormData(schema, {});
+      const emptyFrontmatter = await initializeFormData(
+        schema,
+        {},
+        props.path,
+      );
       frontmatter.value = emptyFrontmatter;
 
       message.success(t("frontmatter.created"));